@@ -1,12 +1,16 @@
 use std::{
     fmt::Display,
+    hash::{Hash, Hasher},
     ops::{Deref, Range},
     str::FromStr,
 };
 
+use eyre::bail;
 use itertools::Itertools;
 
-use crate::{monomer::Monomer, monomers_to_hor};
+use crate::{monomer::Monomer, monomers_to_hor, utils::edit_distance};
+
+use super::{parse::hor_monomer_structure_to_monomers, unit::RepeatUnit};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MonomerUnit {
@@ -15,7 +19,42 @@ pub enum MonomerUnit {
     Chimera(Vec<u8>),
 }
 
-/// An alpha-satellite higher-order repeat composed of one or more [`Monomer`]s.
+/// Compress the numbers of repeat units into [`MonomerUnit`]s.
+/// * Consecutive numbers in the same direction are merged into a [`MonomerUnit::Range`].
+/// * Chimeric units are always a [`MonomerUnit::Chimera`].
+fn units_from_monomers<M: RepeatUnit>(monomers: &[M]) -> Vec<MonomerUnit> {
+    let mut units: Vec<MonomerUnit> = vec![];
+    for mon in monomers {
+        let num = match mon.numbers() {
+            [num] => *num,
+            nums => {
+                units.push(MonomerUnit::Chimera(nums.to_vec()));
+                continue;
+            }
+        };
+        match units.last_mut() {
+            // 4-5 + 6 or 6-5 + 4
+            Some(MonomerUnit::Range(range))
+                if (range.start < range.end && range.end.checked_add(1) == Some(num))
+                    || (range.start > range.end && range.end.checked_sub(1) == Some(num)) =>
+            {
+                range.end = num;
+            }
+            // 4 + 5 or 5 + 4
+            Some(MonomerUnit::Single(prev)) if prev.abs_diff(num) == 1 => {
+                let prev = *prev;
+                units.pop();
+                units.push(MonomerUnit::Range(prev..num));
+            }
+            _ => units.push(MonomerUnit::Single(num)),
+        }
+    }
+    units
+}
+
+/// A higher-order repeat composed of one or more repeat units.
+/// * Alpha-satellite [`Monomer`]s by default. Any [`RepeatUnit`] can be used.
+///
 /// ```
 /// use rs_asat_hor::HOR;
 ///
@@ -24,9 +63,9 @@ pub enum MonomerUnit {
 /// assert_eq!(hor.len(), 6)
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct HOR {
+pub struct HOR<M = Monomer> {
     pub(crate) monomer_structure: Vec<MonomerUnit>,
-    pub(crate) monomers: Vec<Monomer>,
+    pub(crate) monomers: Vec<M>,
 }
 
 impl HOR {
@@ -41,6 +80,26 @@ impl HOR {
     pub fn new(s: &str) -> eyre::Result<Self> {
         HOR::from_str(s)
     }
+}
+
+impl<M: RepeatUnit> HOR<M> {
+    /// Generate a new [`HOR`] from [`MonomerUnit`]s.
+    /// * Repeat units are generated from `unit_base` with the numbers of each [`MonomerUnit`].
+    ///
+    /// ```
+    /// use rs_asat_hor::{Monomer, MonomerUnit, HOR};
+    ///
+    /// let base = Monomer::new("S1C1/5/19H1L.1").unwrap();
+    /// let hor = HOR::from_units(&base, vec![MonomerUnit::Range(1..3), MonomerUnit::Single(5)]);
+    /// assert_eq!(hor, HOR::new("S1C1/5/19H1L.1-3_5").unwrap());
+    /// ```
+    pub fn from_units(unit_base: &M, units: Vec<MonomerUnit>) -> Self {
+        let monomers = hor_monomer_structure_to_monomers(units.iter(), unit_base);
+        Self {
+            monomer_structure: units,
+            monomers,
+        }
+    }
 
     /// Get the number of monomers in a [`HOR`].
     ///
@@ -69,7 +128,7 @@ impl HOR {
     ///     ]
     /// )
     /// ```
-    pub fn monomers(&self) -> &[Monomer] {
+    pub fn monomers(&self) -> &[M] {
         &self.monomers[..]
     }
 
@@ -105,7 +164,7 @@ impl HOR {
     ///     "S1C1/5/19H1L.1-3"
     /// )
     /// ```
-    pub fn from_monomers(monomers: &[Monomer]) -> eyre::Result<Vec<Self>> {
+    pub fn from_monomers(monomers: &[M]) -> eyre::Result<Vec<Self>> {
         monomers_to_hor(monomers.iter(), None)
     }
 
@@ -137,10 +196,10 @@ impl HOR {
             .iter()
             .cloned()
             .rev()
-            .map(|mut mon| {
+            .map(|mon| {
                 // Swap chimeric monomer if present.
-                mon.monomers.reverse();
-                mon
+                let numbers = mon.numbers().iter().rev().cloned().collect_vec();
+                mon.with_numbers(&numbers)
             })
             .collect_vec();
         Self {
@@ -148,11 +207,224 @@ impl HOR {
             monomers: new_monomers,
         }
     }
+
+    /// Similarity between the monomer order of this [`HOR`] and another.
+    /// * Computed as `1 - (edit distance / max number of monomers)`.
+    /// * Only monomer numbers are compared.
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor_1 = HOR::new("S1C1/5/19H1L.1-6").unwrap();
+    /// let hor_2 = HOR::new("S1C1/5/19H1L.1-3_5-6").unwrap();
+    /// assert_eq!(hor_1.similarity(&hor_1), 1.0);
+    /// assert_eq!(hor_1.similarity(&hor_2), 5.0 / 6.0);
+    /// ```
+    pub fn similarity(&self, other: &HOR<M>) -> f32 {
+        let max_len = std::cmp::max(self.n_monomers(), other.n_monomers());
+        if max_len == 0 {
+            return 1.0;
+        }
+        let dst = edit_distance(&self.monomers, &other.monomers, |mon_1, mon_2| {
+            mon_1.numbers() == mon_2.numbers()
+        });
+        1.0 - (dst as f32 / max_len as f32)
+    }
+
+    /// Get the canonical form of this [`HOR`] with the minimal [`MonomerUnit`]s for its monomer order.
+    /// * Use to compare or count StVs written by different tools.
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor = HOR::new("S1C1/5/19H1L.1_2_3-4_5-6").unwrap();
+    /// assert_eq!(hor.normalized().to_string(), "S1C1/5/19H1L.1-6");
+    /// ```
+    pub fn normalized(&self) -> Self {
+        Self::from_monomer_seq(self.monomers.clone())
+    }
+
+    /// Check if this [`HOR`] has the same repeat and monomer order as another, regardless of its [`MonomerUnit`]s.
+    /// * To count or group HORs by monomer order, use [`HOR::key`] as a [`std::collections::HashMap`] key instead.
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor_1 = HOR::new("S1C1/5/19H1L.2-2_3").unwrap();
+    /// let hor_2 = HOR::new("S1C1/5/19H1L.2-3").unwrap();
+    /// assert_ne!(hor_1, hor_2);
+    /// assert!(hor_1.eq_monomer_order(&hor_2));
+    /// ```
+    pub fn eq_monomer_order(&self, other: &HOR<M>) -> bool {
+        self.n_monomers() == other.n_monomers()
+            && self
+                .monomers
+                .iter()
+                .zip(other.monomers.iter())
+                .all(|(mon_1, mon_2)| {
+                    mon_1.numbers() == mon_2.numbers() && mon_1.repeat_name() == mon_2.repeat_name()
+                })
+    }
+
+    /// Get a [`HORKey`] of this [`HOR`] to count or group HORs by monomer order.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rs_asat_hor::{HORKey, HOR};
+    ///
+    /// let mut counts: HashMap<HORKey, usize> = HashMap::new();
+    /// for hor in ["S1C1/5/19H1L.2-2_3", "S1C1/5/19H1L.2-3", "S1C1/5/19H1L.3-2"] {
+    ///     *counts.entry(HOR::new(hor).unwrap().key()).or_default() += 1;
+    /// }
+    /// assert_eq!(counts[&HOR::new("S1C1/5/19H1L.2_3").unwrap().key()], 2);
+    /// assert_eq!(counts.len(), 2);
+    /// ```
+    pub fn key(&self) -> HORKey<M> {
+        HORKey(self.normalized())
+    }
+
+    /// Construct a [`HOR`] from repeat units, recomputing its [`MonomerUnit`]s.
+    fn from_monomer_seq(monomers: Vec<M>) -> Self {
+        Self {
+            monomer_structure: units_from_monomers(&monomers),
+            monomers,
+        }
+    }
+
+    /// Get a new [`HOR`] with the monomers within an index range.
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor = HOR::new("S1C1/5/19H1L.1-3_6/4_5-6").unwrap();
+    /// assert_eq!(hor.slice(1..4).unwrap().to_string(), "S1C1/5/19H1L.2-3_6/4");
+    /// assert!(hor.slice(4..8).is_err());
+    /// ```
+    pub fn slice(&self, range: Range<usize>) -> eyre::Result<Self> {
+        let Some(monomers) = self.monomers.get(range.clone()) else {
+            bail!(
+                "Invalid slice {range:?} of HOR with {} monomers.",
+                self.n_monomers()
+            )
+        };
+        Ok(Self::from_monomer_seq(monomers.to_vec()))
+    }
+
+    /// Split this [`HOR`] into two at a monomer index.
+    /// * The monomer at `idx` is the first monomer of the second [`HOR`].
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor = HOR::new("S1C1/5/19H1L.1-6").unwrap();
+    /// let (left, right) = hor.split_at(2).unwrap();
+    /// assert_eq!(left.to_string(), "S1C1/5/19H1L.1-2");
+    /// assert_eq!(right.to_string(), "S1C1/5/19H1L.3-6");
+    /// ```
+    pub fn split_at(&self, idx: usize) -> eyre::Result<(Self, Self)> {
+        if idx > self.n_monomers() {
+            bail!(
+                "Invalid split position {idx} of HOR with {} monomers.",
+                self.n_monomers()
+            )
+        }
+        let (left, right) = self.monomers.split_at(idx);
+        Ok((
+            Self::from_monomer_seq(left.to_vec()),
+            Self::from_monomer_seq(right.to_vec()),
+        ))
+    }
+
+    /// Concatenate this [`HOR`] with another from the same repeat.
+    ///
+    /// ```
+    /// use rs_asat_hor::HOR;
+    ///
+    /// let hor_1 = HOR::new("S1C1/5/19H1L.4-5").unwrap();
+    /// let hor_2 = HOR::new("S1C1/5/19H1L.6").unwrap();
+    /// assert_eq!(hor_1.concat(&hor_2).unwrap().to_string(), "S1C1/5/19H1L.4-6");
+    ///
+    /// let hor_3 = HOR::new("S2C15H1L.1").unwrap();
+    /// assert!(hor_1.concat(&hor_3).is_err());
+    /// ```
+    pub fn concat(&self, other: &HOR<M>) -> eyre::Result<Self> {
+        let mut hor = self.clone();
+        hor.append(other.monomers.iter().cloned())?;
+        Ok(hor)
+    }
+
+    /// Append repeat units from the same repeat to this [`HOR`].
+    ///
+    /// ```
+    /// use rs_asat_hor::{HOR, Monomer};
+    ///
+    /// let mut hor = HOR::new("S1C1/5/19H1L.4-5").unwrap();
+    /// hor.append([
+    ///     Monomer::new("S1C1/5/19H1L.6").unwrap(),
+    ///     Monomer::new("S1C1/5/19H1L.6/4").unwrap(),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(hor.to_string(), "S1C1/5/19H1L.4-6_6/4");
+    /// ```
+    pub fn append(&mut self, monomers: impl IntoIterator<Item = M>) -> eyre::Result<()> {
+        let mut new_monomers = self.monomers.clone();
+        for mon in monomers {
+            if let Some(first) = new_monomers.first() {
+                let (name, other_name) = (first.repeat_name(), mon.repeat_name());
+                if name != other_name {
+                    bail!("Cannot join {other_name} to HOR of {name}.")
+                }
+            }
+            new_monomers.push(mon);
+        }
+        *self = Self::from_monomer_seq(new_monomers);
+        Ok(())
+    }
+}
+
+/// A [`HOR`] compared and hashed by its repeat and monomer order, regardless of its [`MonomerUnit`]s.
+/// * Equal if [`HOR::eq_monomer_order`]. Wraps the [`HOR::normalized`] form.
+/// * Construct with [`HOR::key`].
+#[derive(Debug, Clone)]
+pub struct HORKey<M = Monomer>(HOR<M>);
+
+impl<M: RepeatUnit> HORKey<M> {
+    /// Get the normalized [`HOR`] of this key.
+    pub fn hor(&self) -> &HOR<M> {
+        &self.0
+    }
+}
+
+impl<M: RepeatUnit> PartialEq for HORKey<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_monomer_order(&other.0)
+    }
+}
+
+impl<M: RepeatUnit> Eq for HORKey<M> {}
+
+impl<M: RepeatUnit> Hash for HORKey<M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0
+            .monomers
+            .first()
+            .map(|mon| mon.repeat_name())
+            .hash(state);
+        for mon in self.0.monomers.iter() {
+            mon.numbers().hash(state);
+        }
+    }
+}
+
+impl<M: RepeatUnit> Display for HORKey<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 // https://stackoverflow.com/a/70547964
-impl IntoIterator for HOR {
-    type Item = Monomer;
+impl<M> IntoIterator for HOR<M> {
+    type Item = M;
     type IntoIter = <Vec<Self::Item> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -160,28 +432,22 @@ impl IntoIterator for HOR {
     }
 }
 
-impl Deref for HOR {
-    type Target = [Monomer];
+impl<M> Deref for HOR<M> {
+    type Target = [M];
 
-    fn deref(&self) -> &[Monomer] {
+    fn deref(&self) -> &[M] {
         &self.monomers[..]
     }
 }
 
-impl Display for HOR {
+impl<M: RepeatUnit> Display for HOR<M> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Some(mon_1) = self.monomers.first() else {
             write!(f, "")?;
             return Ok(());
         };
-        let mon_elems_str = format!("{mon_1}");
-
-        let Some((mon_elems_slice, _)) = mon_elems_str.split_once('.') else {
-            unreachable!("Safe. Should always have . at this point.")
-        };
-
         // Write monomer information.
-        write!(f, "{mon_elems_slice}.")?;
+        write!(f, "{}.", mon_1.repeat_name())?;
 
         for (i, mon_order) in self.monomer_structure.iter().enumerate() {
             match mon_order {
@@ -202,3 +468,103 @@ impl Display for HOR {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use itertools::Itertools;
+
+    use crate::{HORKey, Monomer, HOR};
+
+    #[test]
+    fn test_slice_recompute_units() {
+        let hor = HOR::new("S4CYH1L.46-35_32/34_31/32_31-26_15-1").unwrap();
+        let sliced = hor.slice(10..16).unwrap();
+        assert_eq!(sliced.to_string(), "S4CYH1L.36-35_32/34_31/32_31-30");
+        assert_eq!(sliced.n_monomers(), 6);
+        assert_eq!(sliced.slice(0..0).unwrap().n_monomers(), 0);
+    }
+
+    #[test]
+    fn test_split_invalid() {
+        let hor = HOR::new("S1C1/5/19H1L.1-6").unwrap();
+        assert!(hor.split_at(7).is_err());
+        let (left, right) = hor.split_at(6).unwrap();
+        assert_eq!(left, hor);
+        assert_eq!(right.n_monomers(), 0);
+    }
+
+    #[test]
+    fn test_concat_roundtrip() {
+        let hor = HOR::new("S2C16H2-A.4_7-8_11-9").unwrap();
+        let (left, right) = hor.split_at(3).unwrap();
+        assert_eq!(left.to_string(), "S2C16H2-A.4_7-8");
+        assert_eq!(right.to_string(), "S2C16H2-A.11-9");
+        assert_eq!(left.concat(&right).unwrap(), hor);
+    }
+
+    #[test]
+    fn test_append_merge_desc() {
+        let mut hor = HOR::new("S01/1C3H1L.11-9").unwrap();
+        hor.append([Monomer::new("S01/1C3H1L.8").unwrap()]).unwrap();
+        assert_eq!(hor.to_string(), "S01/1C3H1L.11-8");
+
+        let mut hor = HOR::new("S01/1C3H1L.11").unwrap();
+        hor.append([Monomer::new("S01/1C3H1L.10").unwrap()])
+            .unwrap();
+        assert_eq!(hor, HOR::new("S01/1C3H1L.11-10").unwrap());
+    }
+
+    #[test]
+    fn test_normalized() {
+        for (hor, exp) in [
+            ("S1C1/5/19H1L.3-4_5-6", "S1C1/5/19H1L.3-6"),
+            ("S1C1/5/19H1L.2-2", "S1C1/5/19H1L.2"),
+            ("S1C1/5/19H1L.1_2_3", "S1C1/5/19H1L.1-3"),
+            ("S1C1/5/19H1L.3_2_1_6/4_5", "S1C1/5/19H1L.3-1_6/4_5"),
+            ("S2C16H2-A.4_7-8", "S2C16H2-A.4_7-8"),
+        ] {
+            let hor = HOR::new(hor).unwrap();
+            let exp = HOR::new(exp).unwrap();
+            assert_eq!(hor.normalized(), exp);
+            assert!(hor.eq_monomer_order(&exp));
+        }
+    }
+
+    #[test]
+    fn test_eq_monomer_order_diff() {
+        let hor = HOR::new("S1C1/5/19H1L.1-3").unwrap();
+        assert!(!hor.eq_monomer_order(&HOR::new("S1C1/5/19H1L.1-4").unwrap()));
+        assert!(!hor.eq_monomer_order(&HOR::new("S1C1/5/19H1L.3-1").unwrap()));
+        assert!(!hor.eq_monomer_order(&HOR::new("S2C15H1L.1-3").unwrap()));
+    }
+
+    #[test]
+    fn test_key_count() {
+        let mut counts: HashMap<HORKey, usize> = HashMap::new();
+        for hor in [
+            "S1C1/5/19H1L.1_2_3",
+            "S1C1/5/19H1L.1-3",
+            "S1C1/5/19H1L.1-2_3",
+            "S1C1/5/19H1L.3-1",
+            "S2C15H1L.1-3",
+            "S1C1/5/19H1L.1-2_3/4",
+        ] {
+            *counts.entry(HOR::new(hor).unwrap().key()).or_default() += 1;
+        }
+        assert_eq!(
+            counts
+                .iter()
+                .map(|(key, count)| (key.to_string(), *count))
+                .sorted()
+                .collect_vec(),
+            [
+                ("S1C1/5/19H1L.1-2_3/4".to_string(), 1),
+                ("S1C1/5/19H1L.1-3".to_string(), 3),
+                ("S1C1/5/19H1L.3-1".to_string(), 1),
+                ("S2C15H1L.1-3".to_string(), 1),
+            ]
+        );
+    }
+}
@@ -2,7 +2,8 @@ mod dbg;
 mod hor;
 mod parse;
 mod sfx;
-mod token;
+mod unit;
 
-pub use hor::{MonomerUnit, HOR};
+pub use hor::{HORKey, MonomerUnit, HOR};
 pub(crate) use parse::hor_monomer_structure_to_monomers;
+pub use unit::RepeatUnit;
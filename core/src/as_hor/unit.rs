@@ -0,0 +1,68 @@
+use std::fmt::Debug;
+
+use crate::Strand;
+
+/// A numbered repeat unit that can be composed into a [`crate::HOR`].
+/// * Each unit has one or more numbers. Units with multiple numbers are chimeric.
+/// * The alpha-satellite [`crate::Monomer`] is the default implementation.
+///
+/// ```
+/// use rs_asat_hor::{monomers_to_hor, RepeatUnit, Strand};
+///
+/// // A beta-satellite unit. ex. BSat.3
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct BSat(Vec<u8>);
+///
+/// impl RepeatUnit for BSat {
+///     fn numbers(&self) -> &[u8] {
+///         &self.0
+///     }
+///     fn strand(&self) -> Option<Strand> {
+///         None
+///     }
+///     fn with_numbers(&self, numbers: &[u8]) -> Self {
+///         BSat(numbers.to_vec())
+///     }
+///     fn repeat_name(&self) -> String {
+///         String::from("BSat")
+///     }
+/// }
+///
+/// let units = [BSat(vec![1]), BSat(vec![2]), BSat(vec![3, 4]), BSat(vec![1]), BSat(vec![2])];
+/// let hors = monomers_to_hor(units.iter(), None).unwrap();
+/// assert_eq!(hors[0].to_string(), "BSat.1-2_3/4_1-2");
+/// ```
+pub trait RepeatUnit: Clone + Debug {
+    /// Numbers of this unit in order of the label.
+    fn numbers(&self) -> &[u8];
+
+    /// Strand of this unit, if known.
+    fn strand(&self) -> Option<Strand>;
+
+    /// Construct a unit of the same repeat with different numbers.
+    fn with_numbers(&self, numbers: &[u8]) -> Self;
+
+    /// Name of the repeat without unit numbers.
+    fn repeat_name(&self) -> String;
+
+    /// Check if this unit is chimeric and contains multiple numbers.
+    fn is_chimeric(&self) -> bool {
+        self.numbers().len() > 1
+    }
+
+    /// Get left-most number based on [`RepeatUnit::strand`].
+    fn left_most_num(&self) -> Option<&u8> {
+        match self.strand() {
+            Some(Strand::Minus) => self.numbers().last(),
+            Some(Strand::Plus) | None => self.numbers().first(),
+        }
+    }
+
+    /// Get right-most number based on [`RepeatUnit::strand`].
+    fn right_most_num(&self) -> Option<&u8> {
+        match self.strand() {
+            Some(Strand::Minus) => self.numbers().first(),
+            Some(Strand::Plus) | None => self.numbers().last(),
+        }
+    }
+}
@@ -0,0 +1,612 @@
+//! Grammar of alpha-satellite [`Monomer`] and [`HOR`](crate::HOR) names.
+//!
+//! Both parsers share the same rules so any name accepted by one is written back by
+//! [`Display`](std::fmt::Display) in a canonical form that parses to the same value.
+//! Names already in canonical form are written back unchanged.
+//!
+//! ```text
+//! monomer   = hor-name "." chimera
+//! hor       = hor-name "." structure
+//! hor-name  = "S" sf ("/" sf)* "C" chrom ("/" chrom)* "H" hor-type ["-" desc] [status]
+//! sf        = digit+
+//! chrom     = digit+ | "X" | "Y"
+//! hor-type  = uint                          ; 0 to 4294967295
+//! desc      = word ("-" word)*
+//! word      = (letter | digit)+
+//! status    = "L" | "d"
+//! structure = unit ("_" unit)*
+//! unit      = number "-" number | chimera
+//! chimera   = number ("/" number)*
+//! number    = uint                          ; 0 to 255
+//! uint      = digit+
+//! ```
+//!
+//! * A `desc` that ends in `L` or `d` has that character read as the status.
+//!     * ex. `H2-AL` is descriptor `A` with status [`Status::Live`].
+//!     * ex. `H2-L` has no descriptor and status [`Status::Live`]. It is written as `H2L`.
+//! * A `chimera` can have any number of parts. ex. `6/2/4`
+//! * Numbers with leading zeroes are accepted and written without them. ex. `H01` as `H1`
+//!
+//! [`Monomer::new_lenient`] also accepts legacy variants of a `monomer` and reports each as a [`Normalization`].
+
+use std::{fmt::Display, str::FromStr};
+
+use eyre::bail;
+
+use crate::{
+    monomer::{Chromosome, MonomerHOR, SF},
+    Monomer, MonomerUnit, Status,
+};
+
+/// Kind of legacy naming variant normalized by [`Monomer::new_lenient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NormalizationKind {
+    /// Suprachromosomal family with a `SF` prefix. ex. `SF1` to `S1`
+    SFPrefix,
+    /// Chromosome with a `chr` prefix. ex. `chr1` to `C1`
+    ChromPrefix,
+    /// Lowercase sex chromosome. ex. `x` to `X`
+    ChromCase,
+    /// Status with the wrong case. ex. `l` to `L` or `D` to `d`
+    StatusCase,
+    /// Number with leading zeroes. ex. `01` to `1`
+    LeadingZero,
+    /// Leading or trailing whitespace.
+    Whitespace,
+}
+
+/// A legacy naming variant normalized by [`Monomer::new_lenient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalization {
+    /// `0`-based position in the original name.
+    pub pos: usize,
+    pub kind: NormalizationKind,
+    pub original: String,
+    pub normalized: String,
+}
+
+impl Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Normalized '{}' to '{}' at position {} ({:?}).",
+            self.original, self.normalized, self.pos, self.kind
+        )
+    }
+}
+
+/// Cursor over a name being parsed.
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    /// Accept legacy variants, recording them in `normalized`.
+    lenient: bool,
+    normalized: Vec<Normalization>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str, lenient: bool) -> Self {
+        Self {
+            s,
+            pos: 0,
+            lenient,
+            normalized: vec![],
+        }
+    }
+
+    /// Record a normalization of `s[st..pos]`.
+    fn normalize(&mut self, st: usize, kind: NormalizationKind, normalized: impl Into<String>) {
+        self.normalized.push(Normalization {
+            pos: st,
+            kind,
+            original: self.s[st..self.pos].to_owned(),
+            normalized: normalized.into(),
+        });
+    }
+
+    /// Consume `prefix`, ignoring case, if lenient and it is next.
+    fn eat_lenient(&mut self, prefix: &str) -> bool {
+        let is_next = self.lenient
+            && self
+                .s
+                .get(self.pos..self.pos + prefix.len())
+                .is_some_and(|next| next.eq_ignore_ascii_case(prefix));
+        if is_next {
+            self.pos += prefix.len();
+        }
+        is_next
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consume `c` if it is next.
+    fn eat(&mut self, c: u8) -> bool {
+        let is_next = self.peek() == Some(c);
+        if is_next {
+            self.pos += 1;
+        }
+        is_next
+    }
+
+    fn expect(&mut self, c: u8) -> eyre::Result<()> {
+        if !self.eat(c) {
+            bail!(self.error(&format!("'{}'", c as char)))
+        }
+        Ok(())
+    }
+
+    /// Consume characters while `fn_cond` is true.
+    fn take_while(&mut self, fn_cond: impl Fn(u8) -> bool) -> &'a str {
+        let st = self.pos;
+        while self.peek().is_some_and(&fn_cond) {
+            self.pos += 1;
+        }
+        &self.s[st..self.pos]
+    }
+
+    fn digits(&mut self) -> eyre::Result<&'a str> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            bail!(self.error("number"))
+        }
+        Ok(digits)
+    }
+
+    /// `uint` of type `T`.
+    fn uint<T: FromStr + Display>(&mut self, max: T) -> eyre::Result<T> {
+        let st = self.pos;
+        let digits = self.digits()?;
+        let has_leading_zero = digits.len() > 1 && digits.starts_with('0');
+        let Ok(num) = digits.parse::<T>() else {
+            bail!(
+                "Invalid name, {}. Number ({digits}) at position {st} is greater than {max}.",
+                self.s
+            )
+        };
+        if has_leading_zero {
+            self.normalize(st, NormalizationKind::LeadingZero, num.to_string());
+        }
+        Ok(num)
+    }
+
+    /// `number`
+    fn number(&mut self) -> eyre::Result<u8> {
+        self.uint(u8::MAX)
+    }
+
+    fn error(&self, expected: &str) -> eyre::Report {
+        let found = self
+            .s
+            .get(self.pos..)
+            .and_then(|rest| rest.chars().next())
+            .map(|c| format!("'{c}'"))
+            .unwrap_or_else(|| String::from("end"));
+        eyre::eyre!(
+            "Invalid name, {}. Expected {expected} at position {}, found {found}.",
+            self.s,
+            self.pos
+        )
+    }
+
+    fn finish(&mut self) -> eyre::Result<()> {
+        let st = self.pos;
+        if self.lenient && self.s[st..].bytes().all(|c| c.is_ascii_whitespace()) {
+            self.pos = self.s.len();
+            if st != self.pos {
+                self.normalize(st, NormalizationKind::Whitespace, "");
+            }
+        }
+        if self.peek().is_some() {
+            bail!(self.error("end"))
+        }
+        Ok(())
+    }
+
+    /// `hor-name`. Returns a [`Monomer`] without numbers.
+    fn hor_name(&mut self) -> eyre::Result<Monomer> {
+        if self.lenient {
+            let st = self.pos;
+            self.take_while(|c| c.is_ascii_whitespace());
+            if st != self.pos {
+                self.normalize(st, NormalizationKind::Whitespace, "");
+            }
+        }
+        self.expect(b'S')?;
+        let mut suprachromosomal_family = vec![];
+        loop {
+            // Include the S in the first family. ex. SF1 to S1 but S01/SF1 to 1
+            let st = if suprachromosomal_family.is_empty() {
+                self.pos - 1
+            } else {
+                self.pos
+            };
+            let has_prefix = if suprachromosomal_family.is_empty() {
+                self.eat_lenient("F")
+            } else {
+                self.eat_lenient("SF")
+            };
+            let sf = SF::from_str(self.digits()?)?;
+            if has_prefix {
+                let normalized = if suprachromosomal_family.is_empty() {
+                    format!("S{sf}")
+                } else {
+                    sf.to_string()
+                };
+                self.normalize(st, NormalizationKind::SFPrefix, normalized);
+            }
+            suprachromosomal_family.push(sf);
+            if !self.eat(b'/') {
+                break;
+            }
+        }
+
+        // Allow chr in place of C. ex. S1chr1 or S1Chr1
+        let st = self.pos;
+        if self.eat_lenient("chr") {
+            self.normalize(st, NormalizationKind::ChromPrefix, "C");
+        } else {
+            self.expect(b'C')?;
+        }
+        let mut chromosomes = vec![];
+        loop {
+            let st = self.pos;
+            let has_prefix = self.eat_lenient("chr");
+            let chrom = match self.peek() {
+                Some(c @ (b'X' | b'Y' | b'x' | b'y')) if self.lenient || c.is_ascii_uppercase() => {
+                    self.pos += 1;
+                    Chromosome::from_str(&self.s[self.pos - 1..self.pos].to_ascii_uppercase())?
+                }
+                _ => Chromosome::from_str(self.digits()?)?,
+            };
+            if has_prefix {
+                self.normalize(st, NormalizationKind::ChromPrefix, chrom.to_string());
+            } else if self.s[st..self.pos] != chrom.to_string() {
+                self.normalize(st, NormalizationKind::ChromCase, chrom.to_string());
+            }
+            chromosomes.push(chrom);
+            if !self.eat(b'/') {
+                break;
+            }
+        }
+
+        self.expect(b'H')?;
+        let hor = MonomerHOR(self.uint(u32::MAX)?);
+
+        let mut hor_desc = None;
+        let mut status = None;
+        if self.eat(b'-') {
+            let st = self.pos;
+            loop {
+                if self.take_while(|c| c.is_ascii_alphanumeric()).is_empty() {
+                    bail!(self.error("descriptor"))
+                }
+                if !self.eat(b'-') {
+                    break;
+                }
+            }
+            let desc = &self.s[st..self.pos];
+            if let Some(desc_status) = desc.strip_suffix(['L', 'd']) {
+                // Status is only split off the last word. ex. A-L would leave A-
+                if desc_status.ends_with('-') {
+                    bail!(
+                        "Invalid name, {}. Descriptor ({desc}) at position {st} has no last word before its status.",
+                        self.s
+                    )
+                }
+                status = Some(Status::try_from(desc.chars().last().unwrap())?);
+                // A lone status after the hyphen has no descriptor. ex. H2-L
+                hor_desc = (!desc_status.is_empty()).then(|| desc_status.to_owned());
+            } else {
+                hor_desc = Some(desc.to_owned());
+            }
+        } else if let Some(c @ (b'L' | b'd')) = self.peek() {
+            self.pos += 1;
+            status = Some(Status::try_from(c as char)?);
+        } else if let Some(c @ (b'l' | b'D')) = self.peek().filter(|_| self.lenient) {
+            // Only outside of a descriptor where it can't be part of the descriptor.
+            self.pos += 1;
+            let c = if c == b'l' { 'L' } else { 'd' };
+            self.normalize(self.pos - 1, NormalizationKind::StatusCase, c);
+            status = Some(Status::try_from(c)?);
+        }
+
+        Ok(Monomer {
+            monomers: vec![],
+            suprachromosomal_family,
+            chromosomes,
+            hor,
+            hor_desc,
+            status,
+            strand: None,
+        })
+    }
+
+    /// `chimera` after its first number.
+    fn chimera(&mut self, first: u8) -> eyre::Result<Vec<u8>> {
+        let mut numbers = vec![first];
+        while self.eat(b'/') {
+            numbers.push(self.number()?);
+        }
+        Ok(numbers)
+    }
+
+    /// `unit`
+    fn unit(&mut self) -> eyre::Result<MonomerUnit> {
+        let first = self.number()?;
+        if self.eat(b'-') {
+            return Ok(MonomerUnit::Range(first..self.number()?));
+        }
+        let numbers = self.chimera(first)?;
+        Ok(if numbers.len() == 1 {
+            MonomerUnit::Single(first)
+        } else {
+            MonomerUnit::Chimera(numbers)
+        })
+    }
+}
+
+/// Parse a `monomer`.
+pub(crate) fn parse_monomer(s: &str) -> eyre::Result<Monomer> {
+    parse_monomer_lenient(s, false).map(|(monomer, _)| monomer)
+}
+
+/// Parse a `monomer`, accepting legacy variants if `lenient`.
+pub(crate) fn parse_monomer_lenient(
+    s: &str,
+    lenient: bool,
+) -> eyre::Result<(Monomer, Vec<Normalization>)> {
+    let mut parser = Parser::new(s, lenient);
+    let mut monomer = parser.hor_name()?;
+    parser.expect(b'.')?;
+    let first = parser.number()?;
+    monomer.monomers = parser.chimera(first)?;
+    parser.finish()?;
+    Ok((monomer, parser.normalized))
+}
+
+/// Parse a `hor`.
+///
+/// # Returns
+/// * A [`Monomer`] without numbers to use as the base of each unit and the [`MonomerUnit`]s of the `structure`.
+pub(crate) fn parse_hor(s: &str) -> eyre::Result<(Monomer, Vec<MonomerUnit>)> {
+    let mut parser = Parser::new(s, false);
+    let monomer_base = parser.hor_name()?;
+    parser.expect(b'.')?;
+    let mut units = vec![parser.unit()?];
+    while parser.eat(b'_') {
+        units.push(parser.unit()?);
+    }
+    parser.finish()?;
+    Ok((monomer_base, units))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Monomer, MonomerHOR, MonomerUnit, NormalizationKind, Status, HOR};
+
+    use super::parse_hor;
+
+    #[test]
+    fn test_round_trip_monomer() {
+        for name in [
+            "S1C16H1L.2",
+            "S4CYH1L.46",
+            "S4C20H7.11",
+            "S5C1H6d.1",
+            "S01/1C3H1L.17",
+            "S1C1/5/19H1L.6/4",
+            "S1C10H1L.6/2/4",
+            "S3C1H2-B.4",
+            "S2C2H2-C.6",
+            "S2C2H2-CL.6",
+            "S2C2H2-Ld.6",
+            "S3CXH2-B12d.4",
+            "S1C1H1-AB.1",
+            "S1C1H10L.1",
+            "S2C2H123-AB-C2d.6",
+            "S1C1H0.1",
+        ] {
+            let mon = Monomer::new(name).unwrap();
+            assert_eq!(mon.to_string(), name);
+            assert_eq!(Monomer::new(&mon.to_string()).unwrap(), mon);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_hor() {
+        for name in [
+            "S01/1C3H1L.11",
+            "S01/1C3H1L.11-6",
+            "S2C16H2-A.4_7-8",
+            "S4CYH1L.46-35_32/34_31/32_31-26_15-1",
+            "S1C10H1L.1-5_6/2/4",
+            "S1C10H1L.6/2/4/1_5",
+            "S1C1/5/19H1L.2-2",
+            "S3CXH2-B12d.1-3",
+            "S1C1/5/19H10-AB-CL.1-4_6/4",
+        ] {
+            let hor = HOR::new(name).unwrap();
+            assert_eq!(hor.to_string(), name);
+            assert_eq!(HOR::new(&hor.to_string()).unwrap(), hor);
+        }
+    }
+
+    #[test]
+    fn test_desc_status() {
+        let mon = Monomer::new("S2C2H2-ABd.6").unwrap();
+        assert_eq!(mon.hor_desc.as_deref(), Some("AB"));
+        assert_eq!(mon.status, Some(Status::Divergent));
+
+        // A lone status after the hyphen is only the status.
+        for (name, status, canonical) in [
+            ("S2C2H2-d.6", Status::Divergent, "S2C2H2d.6"),
+            ("S2C2H2-L.6", Status::Live, "S2C2H2L.6"),
+        ] {
+            let mon = Monomer::new(name).unwrap();
+            assert_eq!(mon.hor_desc, None);
+            assert_eq!(mon.status, Some(status));
+            assert_eq!(mon.to_string(), canonical);
+            assert_eq!(Monomer::new(canonical).unwrap(), mon);
+        }
+    }
+
+    #[test]
+    fn test_hor_type() {
+        let mon = Monomer::new("S2C2H12-AB-Cd.6").unwrap();
+        assert_eq!(mon.hor, MonomerHOR(12));
+        assert_eq!(mon.hor_desc.as_deref(), Some("AB-C"));
+        assert_eq!(mon.status, Some(Status::Divergent));
+        assert_eq!(mon.hor_name(), "S2C2H12-AB-Cd");
+
+        let mon = Monomer::new("S2C2H2-A-BL.6").unwrap();
+        assert_eq!(mon.hor_desc.as_deref(), Some("A-B"));
+        assert_eq!(mon.status, Some(Status::Live));
+    }
+
+    #[test]
+    fn test_n_way_chimera() {
+        let mon = Monomer::new("S1C10H1L.6/2/4").unwrap();
+        assert_eq!(mon.monomers, [6, 2, 4]);
+        let (_, units) = parse_hor("S1C10H1L.6/2/4_1").unwrap();
+        assert_eq!(
+            units,
+            [MonomerUnit::Chimera(vec![6, 2, 4]), MonomerUnit::Single(1)]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for name in [
+            "",
+            "S1",
+            "S1C1H1L",
+            "S1C1H1L.",
+            "S1C1H1L.1/",
+            "S1C1H1L.1-2",
+            "S1C1H1L.256",
+            "S1C1H1-.1",
+            "S1C1H1L.1 ",
+            "SC1H1L.1",
+            "S1CH1L.1",
+            "S1C23H1L.1",
+            "S1C1/H1L.1",
+            "1C16H1.2",
+            "S1C1H4294967296L.1",
+            "S1C1H1-A-.1",
+            "S1C1H1--A.1",
+            "S1C1H1-A-L.1",
+            "S1C1H1-A-d.1",
+        ] {
+            assert!(Monomer::new(name).is_err(), "{name}");
+        }
+        for name in [
+            "S1C10H1L._6/2/4",
+            "S1C10H1L.1_",
+            "S1C10H1L.1__2",
+            "S1C10H1L.1-2-3",
+            "S1C10H1L.1-2/3",
+            "S1C10H1L",
+        ] {
+            assert!(HOR::new(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_leading_zero() {
+        for (name, canonical) in [
+            ("S1C1H01L.1", "S1C1H1L.1"),
+            ("S1C1H1L.01", "S1C1H1L.1"),
+            ("S1C1H1L.00", "S1C1H1L.0"),
+        ] {
+            let mon = Monomer::new(name).unwrap();
+            assert_eq!(mon.to_string(), canonical);
+            assert_eq!(Monomer::new(canonical).unwrap(), mon);
+        }
+        assert_eq!(
+            HOR::new("S1C1H1L.01-03_004").unwrap().to_string(),
+            "S1C1H1L.1-3_4"
+        );
+        assert!(Monomer::new("S1C1H1L.0256").is_err());
+    }
+
+    #[test]
+    fn test_lenient() {
+        for (name, expected, kinds) in [
+            ("S1C16H1L.2", "S1C16H1L.2", vec![]),
+            (
+                "SF1C16H1L.2",
+                "S1C16H1L.2",
+                vec![NormalizationKind::SFPrefix],
+            ),
+            (
+                "S01/SF1C3H1L.17",
+                "S01/1C3H1L.17",
+                vec![NormalizationKind::SFPrefix],
+            ),
+            (
+                "S1chr16H1L.2",
+                "S1C16H1L.2",
+                vec![NormalizationKind::ChromPrefix],
+            ),
+            (
+                "S1C1/chr5/chrx/yH1L.2",
+                "S1C1/5/X/YH1L.2",
+                vec![
+                    NormalizationKind::ChromPrefix,
+                    NormalizationKind::ChromPrefix,
+                    NormalizationKind::ChromCase,
+                ],
+            ),
+            (
+                "S1C16H1l.2",
+                "S1C16H1L.2",
+                vec![NormalizationKind::StatusCase],
+            ),
+            (
+                "S5C1H6D.1",
+                "S5C1H6d.1",
+                vec![NormalizationKind::StatusCase],
+            ),
+            ("S4C20H7.11", "S4C20H7.11", vec![]),
+            (
+                " S1C16H01L.02\t",
+                "S1C16H1L.2",
+                vec![
+                    NormalizationKind::Whitespace,
+                    NormalizationKind::LeadingZero,
+                    NormalizationKind::LeadingZero,
+                    NormalizationKind::Whitespace,
+                ],
+            ),
+        ] {
+            let (mon, normalized) = Monomer::new_lenient(name).unwrap();
+            assert_eq!(mon.to_string(), expected, "{name}");
+            assert_eq!(
+                normalized.iter().map(|norm| norm.kind).collect::<Vec<_>>(),
+                kinds,
+                "{name}"
+            );
+            // Normalized names are accepted by the strict parser.
+            assert_eq!(Monomer::new(expected).unwrap(), mon);
+        }
+
+        let (_, normalized) = Monomer::new_lenient("S1Chr16H1L.2").unwrap();
+        assert_eq!(
+            normalized[0].to_string(),
+            "Normalized 'Chr' to 'C' at position 2 (ChromPrefix)."
+        );
+        assert!(Monomer::new_lenient("S1C16H1L").is_err());
+        assert!(Monomer::new("SF1C16H1L.2").is_err());
+    }
+
+    #[test]
+    fn test_error_position() {
+        let err = Monomer::new("S1C1H1L.1/x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid name, S1C1H1L.1/x. Expected number at position 10, found 'x'."
+        );
+    }
+}
@@ -1,7 +1,26 @@
 mod as_hor;
+mod grammar;
 mod monomer;
 mod stv;
+mod utils;
 
-pub use as_hor::{MonomerUnit, HOR};
-pub use monomer::{Monomer, Strand};
-pub use stv::{monomers_to_hor, read_from_monomer_bed, MonomerRecord, StvRecord};
+pub use as_hor::{HORKey, MonomerUnit, RepeatUnit, HOR};
+pub use grammar::{Normalization, NormalizationKind};
+pub use monomer::{Monomer, MonomerHOR, MonomerLibrary, Status, Strand};
+pub use stv::{
+    align_stvs, annotate_monomers, annotate_region, contig_monomers_to_stv_records,
+    divergent_flanks, find_hor_arrays, find_inversions, group_monomer_records, hor_copy_divergence,
+    monomers_to_hor, read_from_monomer_bed, read_from_monomer_bed_in_frame,
+    read_from_monomer_bed_with_report, read_monomer_records, read_monomer_records_with_report,
+    read_monomers_from_bed, relabel_contig_monomers, relabel_monomers, resolve_overlaps,
+    simulate_array, validate_monomer_bed, validate_monomer_bed_reader, write_monomer_fasta,
+    write_monomer_records, write_stv_fasta, write_to_divergence_bed, write_to_inversion_bed,
+    write_to_lifted_bed, write_to_monomer_bed, write_to_stv_bed, AlignedStv, AlignmentMode,
+    AlignmentScoring, AnnotationOpts, ArrayFlanks, ArrayOpts, BedIssue, BedIssueKind, Chain,
+    ChainMap, ChimeraCatalogue, ChimericMonomer, ContigMonomers, CoordFrame, DiscardedMonomer,
+    DivergenceOpts, HORArray, IndexedFasta, Inversion, InversionOpts, Junction, LiftedInterval,
+    LiftedStvRecord, MonomerRecord, OverlapOpts, OverlapPolicy, ReadReport, RecurrentJunction,
+    Region, RelabelOpts, RelabelledMonomer, SelfSimilarity, SharedMonomers, SimulatedArray,
+    SimulationOpts, SkipReason, SkippedRecord, StvAlignment, StvDivergence, StvHit, StvIndex,
+    StvQuery, StvRecord, ValidationOpts,
+};
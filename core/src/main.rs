@@ -0,0 +1,113 @@
+use std::io::{BufWriter, Write};
+
+use eyre::bail;
+use rs_asat_hor::{
+    read_from_monomer_bed, read_from_monomer_bed_in_frame, read_monomer_records,
+    write_monomer_records, write_to_lifted_bed, write_to_stv_bed, ChainMap, CoordFrame, StvQuery,
+};
+
+const USAGE: &str = "\
+Usage:
+    rs-asat-hor liftover [--monomers] <monomers.bed> <chains>
+    rs-asat-hor query <pattern> <monomers.bed>
+
+liftover
+
+Lift StVs from a BED9 file of monomers through a UCSC chain file and write them to stdout.
+* Contigs named name:start-end are shifted into the frame of name first.
+* StVs are written as BED4+2 with the source record and how it mapped (split, partial, strand_changed).
+* Unmapped records are written to stderr.
+
+Options:
+    --monomers  Lift each monomer and write them as BED9 instead.
+
+query
+Write StVs from a BED9 file of monomers matching a query pattern to stdout as BED4.
+* See StvQuery for the pattern syntax. ex. '^11-1$' or '![3-5]'
+";
+
+/// Write StVs matching a [`StvQuery`] pattern as BED4.
+fn query(args: &[String], output: impl Write) -> eyre::Result<()> {
+    let [pattern, bedfile] = args else {
+        bail!("Expected a query pattern and a monomer BED file.\n\n{USAGE}")
+    };
+    let query = StvQuery::new(pattern)?;
+    let records = read_from_monomer_bed(bedfile, |_| false)?;
+    write_to_stv_bed(output, query.filter(&records), CoordFrame::Contig)
+}
+
+/// Lift StVs or monomers through a chain file.
+fn liftover(args: &[String]) -> eyre::Result<()> {
+    let is_monomers = args.iter().any(|arg| arg == "--monomers");
+    let [bedfile, chainfile] = &args
+        .iter()
+        .filter(|arg| *arg != "--monomers")
+        .collect::<Vec<&String>>()[..]
+    else {
+        bail!("Expected a monomer BED file and a chain file.\n\n{USAGE}")
+    };
+    let chain_map = ChainMap::new(chainfile)?;
+    let mut output = BufWriter::new(std::io::stdout().lock());
+
+    if is_monomers {
+        for record in read_monomer_records(bedfile, |_| false)? {
+            let lifted = chain_map.lift_monomer(&record);
+            if lifted.is_empty() {
+                eprintln!("Unmapped\t{record}");
+            }
+            write_monomer_records(&mut output, &lifted)?;
+        }
+    } else {
+        let records = read_from_monomer_bed_in_frame(bedfile, |_| false, CoordFrame::Parent)?;
+        let lifted = records
+            .iter()
+            .map(|rec| chain_map.lift_stv(rec))
+            .collect::<Vec<_>>();
+        for rec in lifted.iter().filter(|rec| rec.is_unmapped()) {
+            let src = &rec.source;
+            eprintln!(
+                "Unmapped\t{}\t{}\t{}\t{}",
+                src.chrom, src.st, src.end, src.hor
+            );
+        }
+        write_to_lifted_bed(&mut output, &lifted)?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+fn main() -> eyre::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    match args.first().map(|arg| arg.as_str()) {
+        Some("liftover") => liftover(&args[1..]),
+        Some("query") => {
+            let mut output = BufWriter::new(std::io::stdout().lock());
+            query(&args[1..], &mut output)?;
+            Ok(output.flush()?)
+        }
+        _ => bail!("{USAGE}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::query;
+
+    #[test]
+    fn test_query() {
+        let args = ["^11-1$", "test/mons.bed"].map(String::from);
+        let mut output = vec![];
+        query(&args, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "chm1_chr15:3977696-8919402\t2732060\t2733936\tS2C15H1L.11-1\n\
+             chm1_chr15:3977696-8919402\t2734617\t2736493\tS2C15H1L.11-1\n"
+        );
+
+        let mut output = vec![];
+        query(&["^1-11$".to_string(), args[1].clone()], &mut output).unwrap();
+        assert!(output.is_empty());
+        assert!(query(&args[..1], vec![]).is_err());
+        assert!(query(&["[".to_string(), args[1].clone()], vec![]).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use eyre::{bail, ContextCompat};
+
+use crate::{utils::reverse_complement, Monomer, RepeatUnit, Strand, HOR};
+
+/// A library of monomer consensus sequences keyed by monomer name.
+/// * ex. The monomer `FASTA` distributed with HumAS-HMMER.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{MonomerLibrary, HOR};
+///
+/// let fa = b">S1C1/5/19H1L.1\nAAAA\n>S1C1/5/19H1L.2\nCCGG\n";
+/// let library = MonomerLibrary::from_reader(&fa[..]).unwrap();
+/// let seq = library.hor_sequence(&HOR::new("S1C1/5/19H1L.1-2").unwrap(), None).unwrap();
+/// assert_eq!(seq, b"AAAACCGG");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MonomerLibrary {
+    sequences: HashMap<String, Vec<u8>>,
+}
+
+impl MonomerLibrary {
+    /// Read monomer sequences from a `FASTA` file.
+    /// * Sequences are keyed by the first word of their header.
+    pub fn new(fasta: impl AsRef<Path>) -> eyre::Result<Self> {
+        let file = std::fs::File::open(fasta)?;
+        MonomerLibrary::from_reader(BufReader::new(file))
+    }
+
+    /// Read monomer sequences in `FASTA` format from a reader.
+    pub fn from_reader(reader: impl BufRead) -> eyre::Result<Self> {
+        let mut sequences: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut curr_seq: Option<&mut Vec<u8>> = None;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('>') {
+                let name = header
+                    .split_whitespace()
+                    .next()
+                    .with_context(|| format!("Empty header at line {}.", i + 1))?;
+                curr_seq = Some(sequences.entry(name.to_owned()).or_default());
+                continue;
+            }
+            let Some(seq) = curr_seq.as_mut() else {
+                bail!("Sequence without header at line {}.", i + 1)
+            };
+            seq.extend(line.as_bytes());
+        }
+        Ok(Self { sequences })
+    }
+
+    /// Number of monomer sequences.
+    pub fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    /// Check if library has no sequences.
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    /// Iterate through monomer names and their sequences in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.sequences
+            .iter()
+            .map(|(name, seq)| (name.as_str(), seq.as_slice()))
+    }
+
+    /// Get the consensus sequence of a monomer in its labelled orientation.
+    /// * A chimeric monomer missing from the library is built by splitting its length evenly between its parents.
+    ///     * ex. `6/4` is the first half of `6` and the second half of `4`.
+    ///
+    /// # Args
+    /// * `monomer`
+    ///     * [`Monomer`] to get. [`Monomer::strand`] is ignored.
+    ///
+    /// # Returns
+    /// * Sequence or an error if the monomer or any of its parents are missing.
+    pub fn monomer_sequence(&self, monomer: &Monomer) -> eyre::Result<Vec<u8>> {
+        if let Some(seq) = self.sequences.get(&monomer.to_string()) {
+            return Ok(seq.clone());
+        }
+        if !monomer.is_chimeric() {
+            bail!("Monomer {monomer} not in library.")
+        }
+        let parents = monomer
+            .monomers
+            .iter()
+            .map(|num| {
+                let parent = monomer.with_numbers(&[*num]);
+                self.sequences
+                    .get(&parent.to_string())
+                    .with_context(|| format!("Parent {parent} of {monomer} not in library."))
+            })
+            .collect::<eyre::Result<Vec<&Vec<u8>>>>()?;
+
+        // Take each parent's segment at its position along the monomer.
+        let n_parents = parents.len();
+        let mut seq = vec![];
+        for (i, parent) in parents.into_iter().enumerate() {
+            let st = parent.len() * i / n_parents;
+            let end = parent.len() * (i + 1) / n_parents;
+            seq.extend(&parent[st..end]);
+        }
+        Ok(seq)
+    }
+
+    /// Get the consensus sequence of a [`HOR`] by concatenating its monomer sequences.
+    /// * Monomers are kept in order and the sequence is on the `+` strand of the genome.
+    /// * Monomers on the [`Strand::Minus`] are reverse complemented.
+    ///
+    /// # Args
+    /// * `hor`
+    ///     * [`HOR`] to build.
+    /// * `strand`
+    ///     * [`Strand`] of all monomers. Uses each [`Monomer::strand`], or [`Strand::Plus`] if missing, if omitted.
+    ///
+    /// # Returns
+    /// * Sequence or an error if any monomer is missing.
+    pub fn hor_sequence(&self, hor: &HOR, strand: Option<Strand>) -> eyre::Result<Vec<u8>> {
+        let mut seq = vec![];
+        for mon in hor.monomers() {
+            let mon_seq = self.monomer_sequence(mon)?;
+            match strand.or(mon.strand).unwrap_or(Strand::Plus) {
+                Strand::Plus => seq.extend(mon_seq),
+                Strand::Minus => seq.extend(reverse_complement(&mon_seq)),
+            }
+        }
+        Ok(seq)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Monomer, MonomerLibrary, Strand, HOR};
+
+    const FASTA: &[u8] =
+        b">S1C1/5/19H1L.4 desc\nAAAA\nCCCC\n>S1C1/5/19H1L.5\nACGT\n>S1C1/5/19H1L.6\nGGGGTTTT\n";
+
+    #[test]
+    fn test_library_read() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        assert_eq!(library.len(), 3);
+        assert_eq!(
+            library
+                .monomer_sequence(&Monomer::new("S1C1/5/19H1L.4").unwrap())
+                .unwrap(),
+            b"AAAACCCC"
+        );
+        assert!(MonomerLibrary::from_reader(&b"ACGT\n>S1C1/5/19H1L.4\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_library_chimera() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let mon = Monomer::new("S1C1/5/19H1L.6/4").unwrap();
+        assert_eq!(library.monomer_sequence(&mon).unwrap(), b"GGGGCCCC");
+        // Missing parent.
+        let mon = Monomer::new("S1C1/5/19H1L.6/1").unwrap();
+        assert!(library.monomer_sequence(&mon).is_err());
+    }
+
+    #[test]
+    fn test_library_hor_strand() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let hor = HOR::new("S1C1/5/19H1L.4-5").unwrap();
+        assert_eq!(library.hor_sequence(&hor, None).unwrap(), b"AAAACCCCACGT");
+        assert_eq!(
+            library.hor_sequence(&hor, Some(Strand::Minus)).unwrap(),
+            b"GGGGTTTTACGT"
+        );
+        assert!(library
+            .hor_sequence(&HOR::new("S1C1/5/19H1L.1-5").unwrap(), None)
+            .is_err());
+    }
+}
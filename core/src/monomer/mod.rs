@@ -1,11 +1,16 @@
 mod chrom;
+mod library;
 mod mon;
 mod mon_type;
 mod ord;
 mod parse;
 mod sf;
 mod status;
-mod token;
 
+pub(crate) use chrom::Chromosome;
+pub use library::MonomerLibrary;
 pub use mon::Monomer;
+pub use mon_type::MonomerHOR;
 pub use ord::Strand;
+pub(crate) use sf::SF;
+pub use status::Status;
@@ -2,6 +2,11 @@ use std::{fmt::Display, str::FromStr};
 
 use itertools::Itertools;
 
+use crate::{
+    grammar::{parse_monomer_lenient, Normalization},
+    RepeatUnit,
+};
+
 use super::{chrom::Chromosome, mon_type::MonomerHOR, ord::Strand, sf::SF, status::Status};
 
 /// An alpha-satellite higher-order repeat monomer.
@@ -35,6 +40,36 @@ impl Monomer {
         Monomer::from_str(s)
     }
 
+    /// Construct a new [`Monomer`] from a given string, accepting legacy naming variants.
+    /// * Suprachromosomal families with a `SF` prefix. ex. `SF1C1H1L.1` or `S01/SF1C3H1L.1`
+    /// * Chromosomes with a `chr` prefix or lowercase. ex. `S1chr1H1L.1` or `S1C1/chr5/chrxH1L.1`
+    /// * Status with the wrong case. ex. `S1C1H1l.1` or `S5C1H6D.1`
+    /// * Numbers with leading zeroes, which [`Monomer::new`] also accepts. ex. `S1C1H01L.01`
+    /// * Leading or trailing whitespace.
+    ///
+    /// Missing status is accepted by [`Monomer::new`] and isn't normalized.
+    ///
+    /// # Returns
+    /// * The normalized [`Monomer`] and each [`Normalization`] applied, in order of position.
+    ///
+    /// ```
+    /// use rs_asat_hor::{Monomer, NormalizationKind};
+    ///
+    /// let (mon, normalized) = Monomer::new_lenient("SF1chr16H1l.2").unwrap();
+    /// assert_eq!(mon, Monomer::new("S1C16H1L.2").unwrap());
+    /// assert_eq!(
+    ///     normalized.iter().map(|norm| norm.kind).collect::<Vec<_>>(),
+    ///     [
+    ///         NormalizationKind::SFPrefix,
+    ///         NormalizationKind::ChromPrefix,
+    ///         NormalizationKind::StatusCase
+    ///     ]
+    /// );
+    /// ```
+    pub fn new_lenient(s: &str) -> eyre::Result<(Self, Vec<Normalization>)> {
+        parse_monomer_lenient(s, true)
+    }
+
     /// Add [`Strand`] information. Affects chimeric monomer comparison with other [`Monomer`]s.
     /// * If omitted, the default ordering (assumed `+`) is retained.
     /// * Does not alter [`Monomer::monomers`].
@@ -67,16 +102,21 @@ impl Monomer {
     pub fn is_chimeric(&self) -> bool {
         self.monomers.len() > 1
     }
-}
 
-impl Display for Monomer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// Get the name of the HOR this [`Monomer`] belongs to, without monomer numbers.
+    ///
+    /// ```
+    /// use rs_asat_hor::Monomer;
+    ///
+    /// let mon = Monomer::new("S1C1/5/19H1L.4/6").unwrap();
+    /// assert_eq!(mon.hor_name(), "S1C1/5/19H1L");
+    /// ```
+    pub fn hor_name(&self) -> String {
         let status = match self.status {
             Some(Status::Live) => "L",
             Some(Status::Divergent) => "d",
             None => "",
         };
-        let monomers = self.monomers.iter().join("/");
         let hor_desc = self
             .hor_desc
             .as_ref()
@@ -84,10 +124,33 @@ impl Display for Monomer {
             .unwrap_or_default();
         let chromosomes = self.chromosomes.iter().join("/");
         let sfs = self.suprachromosomal_family.iter().join("/");
-        write!(
-            f,
-            "S{}C{}{:?}{hor_desc}{status}.{monomers}",
-            sfs, chromosomes, self.hor,
-        )
+        format!("S{}C{}{}{hor_desc}{status}", sfs, chromosomes, self.hor)
+    }
+}
+
+impl RepeatUnit for Monomer {
+    fn numbers(&self) -> &[u8] {
+        &self.monomers
+    }
+
+    fn strand(&self) -> Option<Strand> {
+        self.strand
+    }
+
+    fn with_numbers(&self, numbers: &[u8]) -> Self {
+        let mut mon = self.clone();
+        mon.monomers = numbers.to_vec();
+        mon
+    }
+
+    fn repeat_name(&self) -> String {
+        self.hor_name()
+    }
+}
+
+impl Display for Monomer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let monomers = self.monomers.iter().join("/");
+        write!(f, "{}.{monomers}", self.hor_name())
     }
 }
@@ -2,55 +2,48 @@ use std::{fmt::Display, str::FromStr};
 
 use eyre::bail;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum MonomerHOR {
-    H1,
-    H2,
-    H3,
-    H4,
-    H5,
-    H6,
-    H7,
-    H8,
-    H9,
+/// HOR type number of a [`crate::Monomer`]. ex. `H1` or `H10`.
+///
+/// ```
+/// use rs_asat_hor::{Monomer, MonomerHOR};
+///
+/// let mon = Monomer::new("S1C1H10L.2").unwrap();
+/// assert_eq!(mon.hor, MonomerHOR(10));
+/// assert_eq!(mon.hor.to_string(), "H10");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MonomerHOR(pub u32);
+
+impl MonomerHOR {
+    pub const H1: MonomerHOR = MonomerHOR(1);
+    pub const H2: MonomerHOR = MonomerHOR(2);
+    pub const H3: MonomerHOR = MonomerHOR(3);
+    pub const H4: MonomerHOR = MonomerHOR(4);
+    pub const H5: MonomerHOR = MonomerHOR(5);
+    pub const H6: MonomerHOR = MonomerHOR(6);
+    pub const H7: MonomerHOR = MonomerHOR(7);
+    pub const H8: MonomerHOR = MonomerHOR(8);
+    pub const H9: MonomerHOR = MonomerHOR(9);
 }
 
 impl FromStr for MonomerHOR {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "H1" => MonomerHOR::H1,
-            "H2" => MonomerHOR::H2,
-            "H3" => MonomerHOR::H3,
-            "H4" => MonomerHOR::H4,
-            "H5" => MonomerHOR::H5,
-            "H6" => MonomerHOR::H6,
-            "H7" => MonomerHOR::H7,
-            "H8" => MonomerHOR::H8,
-            "H9" => MonomerHOR::H9,
-            _ => bail!("Unknown monomer type, {s}."),
-        })
+        let Some(num) = s
+            .strip_prefix('H')
+            .filter(|num| num.bytes().all(|c| c.is_ascii_digit()))
+            .and_then(|num| num.parse().ok())
+        else {
+            bail!("Unknown monomer type, {s}.")
+        };
+        Ok(MonomerHOR(num))
     }
 }
 
 impl Display for MonomerHOR {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                MonomerHOR::H1 => "H1",
-                MonomerHOR::H2 => "H2",
-                MonomerHOR::H3 => "H3",
-                MonomerHOR::H4 => "H4",
-                MonomerHOR::H5 => "H5",
-                MonomerHOR::H6 => "H6",
-                MonomerHOR::H7 => "H7",
-                MonomerHOR::H8 => "H8",
-                MonomerHOR::H9 => "H9",
-            }
-        )
+        write!(f, "H{}", self.0)
     }
 }
 
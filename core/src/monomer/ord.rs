@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use eyre::bail;
 
@@ -22,6 +22,15 @@ impl FromStr for Strand {
     }
 }
 
+impl Display for Strand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strand::Plus => write!(f, "+"),
+            Strand::Minus => write!(f, "-"),
+        }
+    }
+}
+
 impl Monomer {
     /// Get right-most mon based on [`Monomer::strand`].
     /// * If not chimeric, return the only number.
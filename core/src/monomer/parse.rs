@@ -1,137 +1,14 @@
-use eyre::{bail, ContextCompat};
 use std::str::FromStr;
 
-use itertools::Itertools;
-
-use super::{
-    chrom::Chromosome, mon::Monomer, mon_type::MonomerHOR, sf::SF, status::Status, token::Token,
-};
+use super::mon::Monomer;
 
+/// Parse a [`Monomer`] name with the same grammar as [`crate::HOR`] names.
+/// * ex. `S1C1/5/19H1L.6/4` or `S3C1H2-B.4`
 impl FromStr for Monomer {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut monomers: Vec<u8> = vec![];
-        let mut suprachromosomal_family: Vec<SF> = Vec::with_capacity(2);
-        let mut chromosomes: Vec<Chromosome> = vec![];
-        let mut hor: Option<MonomerHOR> = None;
-        let mut hor_desc: Option<String> = None;
-        let mut status: Option<Status> = None;
-
-        // TODO: Bookkeeping for position.
-        // Create peekable iterator.
-        let tokens = &s.chars().chunk_by(|c| Token::from(*c));
-        let mut tokens_iter = tokens.into_iter().peekable();
-
-        while let Some((token, values)) = tokens_iter.next() {
-            match token {
-                Token::SF => {
-                    while let Some((tk, sf_values)) =
-                        tokens_iter.next_if(|(tk, _)| *tk == Token::Number || *tk == Token::Chimera)
-                    {
-                        // Skip / in 1/01
-                        if tk == Token::Chimera {
-                            continue;
-                        }
-                        let sf_str = String::from_iter(sf_values);
-                        suprachromosomal_family.push(SF::from_str(&sf_str)?);
-                    }
-                }
-                Token::Chrom => {
-                    while let Some((tk, chr_values)) = tokens_iter.next_if(|(tk, _)| {
-                        let tk_is_num = *tk == Token::Number;
-                        let tk_is_alpha = std::mem::discriminant(tk)
-                            == std::mem::discriminant(&Token::Value('a'));
-                        let tk_is_delim = *tk == Token::Chimera;
-                        tk_is_alpha || tk_is_num || tk_is_delim
-                    }) {
-                        // Skip / in cases like 1/5/19
-                        if tk == Token::Chimera {
-                            continue;
-                        }
-                        let chrom_str = String::from_iter(chr_values);
-                        chromosomes.push(Chromosome::from_str(&chrom_str)?);
-                    }
-                }
-                Token::Monomer => {
-                    let Some((_, mon_values)) = tokens_iter.next_if(|(tk, _)| *tk == Token::Number)
-                    else {
-                        bail!("No numeric value after '.'");
-                    };
-                    let mon1_str = String::from_iter(mon_values);
-                    monomers.push(u8::from_str(&mon1_str)?);
-
-                    let Some(_) = tokens_iter.next_if(|(tk, _)| *tk == Token::Chimera) else {
-                        continue;
-                    };
-                    let Some((_, mon_2_values)) =
-                        tokens_iter.next_if(|(tk, _)| *tk == Token::Number)
-                    else {
-                        bail!(
-                            "Unexpected token, {:?}, after chimeric monomer delimiter.",
-                            tokens_iter
-                                .next()
-                                .map(|(_, tk_vals)| tk_vals.into_iter().join(","))
-                        );
-                    };
-                    let mon2_str = String::from_iter(mon_2_values);
-                    monomers.push(u8::from_str(&mon2_str)?);
-                }
-                Token::Live | Token::Divergent => {
-                    status = Some(Status::try_from(token.char())?);
-                }
-                Token::MType => {
-                    // Take num
-                    let Some((_, mtype_vals)) = tokens_iter.next_if(|(tk, _)| *tk == Token::Number)
-                    else {
-                        bail!(
-                            "Unexpected token, {:?}, after chimeric monomer delimiter.",
-                            tokens_iter
-                                .next()
-                                .map(|(_, tk_vals)| tk_vals.into_iter().join(","))
-                        )
-                    };
-                    let mut mtype = String::from_iter(mtype_vals);
-                    mtype.insert(0, 'H');
-                    hor = Some(MonomerHOR::from_str(&mtype)?);
-
-                    // Hyphen found. Is commented.
-                    let Some(_) = tokens_iter.next_if(|(tk, _)| *tk == Token::Hyphen) else {
-                        continue;
-                    };
-                    // For mtype comment.
-                    // Consume values until non-value.
-                    let mut monomer_desc = String::new();
-                    while let Some((desc_token, _)) = tokens_iter.next_if(|(tk, _)| {
-                        std::mem::discriminant(tk) == std::mem::discriminant(&Token::Value('a')) ||
-                        // Edge case since C can be chrom or a comment.
-                        tk.char() == 'C'
-                    }) {
-                        monomer_desc.push(desc_token.char());
-                    }
-                    hor_desc = (!monomer_desc.is_empty()).then_some(monomer_desc);
-                }
-                Token::Hyphen | Token::Number | Token::Chimera => {
-                    bail!(
-                        "Invalid monomer str, {s}. Unconsumed token, {}.",
-                        values.into_iter().join(",")
-                    )
-                }
-                Token::Value(v) => {
-                    bail!("Invalid monomer str, {s}. Unknown character, {v}.")
-                }
-            }
-        }
-
-        Ok(Monomer {
-            monomers,
-            suprachromosomal_family,
-            chromosomes,
-            hor: hor.with_context(|| format!("Invalid monomer, {s}. Monomer type is required."))?,
-            hor_desc,
-            status,
-            strand: None,
-        })
+        crate::grammar::parse_monomer(s)
     }
 }
 
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::HOR;
+
+use super::io::StvRecord;
+
+/// Alignment mode of [`align_stvs`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlignmentMode {
+    /// Align both StV sequences end-to-end.
+    #[default]
+    Global,
+    /// Don't penalize leading or trailing gaps in either StV sequence.
+    /// * Use if either array is truncated.
+    SemiGlobal,
+}
+
+/// Scores used by [`align_stvs`].
+/// * A pair of StVs is scored by interpolating between `mismatch` and `matched` by [`crate::HOR::similarity`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignmentScoring {
+    pub matched: f32,
+    pub mismatch: f32,
+    pub gap: f32,
+}
+
+impl Default for AlignmentScoring {
+    fn default() -> Self {
+        Self {
+            matched: 1.0,
+            mismatch: -1.0,
+            gap: -1.0,
+        }
+    }
+}
+
+/// An aligned pair of StVs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlignedStv<'a> {
+    /// StV in both arrays.
+    Match {
+        target: &'a StvRecord,
+        query: &'a StvRecord,
+        similarity: f32,
+    },
+    /// StV only in query array.
+    Insertion(&'a StvRecord),
+    /// StV only in target array.
+    Deletion(&'a StvRecord),
+}
+
+/// Alignment of two ordered StV arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StvAlignment<'a> {
+    pub score: f32,
+    pub aligned: Vec<AlignedStv<'a>>,
+}
+
+impl<'a> StvAlignment<'a> {
+    /// Get matched StVs as `(target, query, similarity)`.
+    pub fn matches(&self) -> impl Iterator<Item = (&'a StvRecord, &'a StvRecord, f32)> + '_ {
+        self.aligned.iter().filter_map(|aln| match aln {
+            AlignedStv::Match {
+                target,
+                query,
+                similarity,
+            } => Some((*target, *query, *similarity)),
+            _ => None,
+        })
+    }
+
+    /// Get StVs only in the query array.
+    pub fn insertions(&self) -> impl Iterator<Item = &'a StvRecord> + '_ {
+        self.aligned.iter().filter_map(|aln| match aln {
+            AlignedStv::Insertion(rec) => Some(*rec),
+            _ => None,
+        })
+    }
+
+    /// Get StVs only in the target array.
+    pub fn deletions(&self) -> impl Iterator<Item = &'a StvRecord> + '_ {
+        self.aligned.iter().filter_map(|aln| match aln {
+            AlignedStv::Deletion(rec) => Some(*rec),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Traceback {
+    Start,
+    Diagonal,
+    Up,
+    Left,
+}
+
+/// Compute the [`crate::HOR::similarity`] of all unique StV pairs.
+/// * StVs are mostly repeated so this avoids recomputing the similarity for each cell.
+fn unique_similarities(
+    target: &[StvRecord],
+    query: &[StvRecord],
+) -> (Vec<usize>, Vec<usize>, Vec<Vec<f32>>) {
+    fn fn_unique(records: &[StvRecord]) -> (Vec<usize>, Vec<&HOR>) {
+        let mut uniq: HashMap<String, usize> = HashMap::new();
+        let mut uniq_hors = vec![];
+        let idxs = records
+            .iter()
+            .map(|rec| {
+                *uniq.entry(rec.hor.to_string()).or_insert_with(|| {
+                    uniq_hors.push(&rec.hor);
+                    uniq_hors.len() - 1
+                })
+            })
+            .collect_vec();
+        (idxs, uniq_hors)
+    }
+    let (target_idxs, target_hors) = fn_unique(target);
+    let (query_idxs, query_hors) = fn_unique(query);
+    let similarities = target_hors
+        .iter()
+        .map(|t_hor| {
+            query_hors
+                .iter()
+                .map(|q_hor| t_hor.similarity(q_hor))
+                .collect()
+        })
+        .collect();
+    (target_idxs, query_idxs, similarities)
+}
+
+/// Align two ordered arrays of [`StvRecord`]s.
+/// * StVs are scored by [`crate::HOR::similarity`] rather than string equality.
+/// * Records should be from the same strand and sorted by position.
+///
+/// # Args
+/// * `target`
+///     * Target StVs. StVs only in the target are [`AlignedStv::Deletion`]s.
+/// * `query`
+///     * Query StVs. StVs only in the query are [`AlignedStv::Insertion`]s.
+/// * `mode`
+///     * [`AlignmentMode`]
+/// * `scoring`
+///     * [`AlignmentScoring`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`StvAlignment`] in order of the input arrays.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{align_stvs, AlignmentMode, StvRecord, HOR};
+///
+/// let hor = HOR::new("S1C1/5/19H1L.1-6").unwrap();
+/// let hor_del = HOR::new("S1C1/5/19H1L.1-3_6").unwrap();
+/// let target = [
+///     StvRecord::new("hap1", 0, 1020, hor.clone()),
+///     StvRecord::new("hap1", 1020, 2040, hor.clone()),
+///     StvRecord::new("hap1", 2040, 3060, hor.clone()),
+/// ];
+/// let query = [
+///     StvRecord::new("hap2", 0, 1020, hor.clone()),
+///     StvRecord::new("hap2", 1020, 1700, hor_del),
+/// ];
+/// let aln = align_stvs(&target, &query, AlignmentMode::Global, None);
+/// assert_eq!(aln.matches().count(), 2);
+/// assert_eq!(aln.deletions().count(), 1);
+/// ```
+pub fn align_stvs<'a>(
+    target: &'a [StvRecord],
+    query: &'a [StvRecord],
+    mode: AlignmentMode,
+    scoring: Option<AlignmentScoring>,
+) -> StvAlignment<'a> {
+    let scoring = scoring.unwrap_or_default();
+    let (target_idxs, query_idxs, similarities) = unique_similarities(target, query);
+    let fn_sub_score = |sim: f32| scoring.mismatch + sim * (scoring.matched - scoring.mismatch);
+    let end_gap = match mode {
+        AlignmentMode::Global => scoring.gap,
+        AlignmentMode::SemiGlobal => 0.0,
+    };
+
+    let (n, m) = (target.len(), query.len());
+    let mut scores = vec![vec![0.0f32; m + 1]; n + 1];
+    let mut traceback = vec![vec![Traceback::Start; m + 1]; n + 1];
+    for i in 1..=n {
+        scores[i][0] = scores[i - 1][0] + end_gap;
+        traceback[i][0] = Traceback::Up;
+    }
+    for j in 1..=m {
+        scores[0][j] = scores[0][j - 1] + end_gap;
+        traceback[0][j] = Traceback::Left;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let sim = similarities[target_idxs[i - 1]][query_idxs[j - 1]];
+            // Trailing gaps are free in semi-global mode.
+            let gap_up = if j == m { end_gap } else { scoring.gap };
+            let gap_left = if i == n { end_gap } else { scoring.gap };
+            let (score, tb) = [
+                (
+                    scores[i - 1][j - 1] + fn_sub_score(sim),
+                    Traceback::Diagonal,
+                ),
+                (scores[i - 1][j] + gap_up, Traceback::Up),
+                (scores[i][j - 1] + gap_left, Traceback::Left),
+            ]
+            .into_iter()
+            .reduce(|best, other| if other.0 > best.0 { other } else { best })
+            .unwrap();
+            scores[i][j] = score;
+            traceback[i][j] = tb;
+        }
+    }
+
+    let mut aligned = vec![];
+    let (mut i, mut j) = (n, m);
+    loop {
+        match traceback[i][j] {
+            Traceback::Start => break,
+            Traceback::Diagonal => {
+                aligned.push(AlignedStv::Match {
+                    target: &target[i - 1],
+                    query: &query[j - 1],
+                    similarity: similarities[target_idxs[i - 1]][query_idxs[j - 1]],
+                });
+                i -= 1;
+                j -= 1;
+            }
+            Traceback::Up => {
+                aligned.push(AlignedStv::Deletion(&target[i - 1]));
+                i -= 1;
+            }
+            Traceback::Left => {
+                aligned.push(AlignedStv::Insertion(&query[j - 1]));
+                j -= 1;
+            }
+        }
+    }
+    aligned.reverse();
+
+    StvAlignment {
+        score: scores[n][m],
+        aligned,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::{align_stvs, stv::test_utils::tiled_records, AlignedStv, AlignmentMode};
+
+    #[test]
+    fn test_align_identical() {
+        let hors = ["S1C1/5/19H1L.1-6", "S1C1/5/19H1L.1-4_6", "S1C1/5/19H1L.1-6"];
+        let target = tiled_records("hap1", &hors, 1020);
+        let query = tiled_records("hap2", &hors, 1020);
+        let aln = align_stvs(&target, &query, AlignmentMode::Global, None);
+        assert_eq!(aln.score, 3.0);
+        assert_eq!(aln.matches().count(), 3);
+        assert!(aln.matches().all(|(_, _, sim)| sim == 1.0));
+    }
+
+    #[test]
+    fn test_align_expansion() {
+        let target = tiled_records(
+            "hap1",
+            &["S1C1/5/19H1L.1-6", "S1C1/5/19H1L.1-4_6", "S1C1/5/19H1L.1-6"],
+            1020,
+        );
+        let query = tiled_records(
+            "hap2",
+            &[
+                "S1C1/5/19H1L.1-6",
+                "S1C1/5/19H1L.1-4_6",
+                "S1C1/5/19H1L.1-4_6",
+                "S1C1/5/19H1L.1-6",
+            ],
+            1020,
+        );
+        let aln = align_stvs(&target, &query, AlignmentMode::Global, None);
+        assert_eq!(aln.insertions().count(), 1);
+        assert_eq!(aln.deletions().count(), 0);
+        assert_eq!(
+            aln.insertions().next().unwrap().hor.to_string(),
+            "S1C1/5/19H1L.1-4_6"
+        );
+    }
+
+    #[test]
+    fn test_align_semi_global() {
+        let target = tiled_records(
+            "hap1",
+            &["S1C1/5/19H1L.1-2", "S1C1/5/19H1L.1-6", "S1C1/5/19H1L.1-4_6"],
+            1020,
+        );
+        let query = tiled_records("hap2", &["S1C1/5/19H1L.1-6", "S1C1/5/19H1L.1-4_6"], 1020);
+
+        // Leading deletion is free.
+        let aln = align_stvs(&target, &query, AlignmentMode::SemiGlobal, None);
+        assert_eq!(aln.score, 2.0);
+        assert_eq!(
+            aln.aligned
+                .iter()
+                .map(|aln| match aln {
+                    AlignedStv::Match { target, query, .. } => (Some(target.st), Some(query.st)),
+                    AlignedStv::Insertion(query) => (None, Some(query.st)),
+                    AlignedStv::Deletion(target) => (Some(target.st), None),
+                })
+                .collect_vec(),
+            [
+                (Some(0), None),
+                (Some(1020), Some(0)),
+                (Some(2040), Some(1020))
+            ]
+        );
+
+        let aln = align_stvs(&target, &query, AlignmentMode::Global, None);
+        assert_eq!(aln.score, 1.0);
+    }
+
+    #[test]
+    fn test_align_empty() {
+        let target = tiled_records("hap1", &["S1C1/5/19H1L.1-6"], 1020);
+        let aln = align_stvs(&target, &[], AlignmentMode::Global, None);
+        assert_eq!(aln.deletions().count(), 1);
+        assert_eq!(aln.score, -1.0);
+    }
+}
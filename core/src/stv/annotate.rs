@@ -0,0 +1,264 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+};
+
+use itertools::Itertools;
+
+use crate::{utils::reverse_complement, Monomer, MonomerLibrary, Strand};
+
+use super::{fasta::IndexedFasta, io::ContigMonomers, region::Region};
+
+/// Options for [`annotate_monomers`] and [`annotate_region`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnnotationOpts {
+    /// Minimum percent identity to the best library monomer to label a monomer.
+    pub min_identity: f32,
+}
+
+impl Default for AnnotationOpts {
+    fn default() -> Self {
+        Self { min_identity: 70.0 }
+    }
+}
+
+/// Fit `query` end-to-end within `target`, allowing free leading and trailing gaps in `target`.
+///
+/// # Returns
+/// * `(st, end, edit distance)` of the best fit in `target`. Ties are broken by the right-most end.
+fn fit_alignment(query: &[u8], target: &[u8]) -> (usize, usize, usize) {
+    // Each cell is (edit distance, start in target).
+    let mut prev_row = (0..=target.len()).map(|j| (0, j)).collect_vec();
+    let mut curr_row = vec![(0, 0); target.len() + 1];
+    for (i, q) in query.iter().enumerate() {
+        curr_row[0] = (i + 1, 0);
+        for (j, t) in target.iter().enumerate() {
+            let cost = usize::from(!q.eq_ignore_ascii_case(t));
+            curr_row[j + 1] = [
+                (prev_row[j].0 + cost, prev_row[j].1),
+                (prev_row[j + 1].0 + 1, prev_row[j + 1].1),
+                (curr_row[j].0 + 1, curr_row[j].1),
+            ]
+            .into_iter()
+            .min_by_key(|(dst, _)| *dst)
+            .unwrap();
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+    let (end, (dst, st)) = prev_row
+        .into_iter()
+        .enumerate()
+        .min_by_key(|(end, (dst, _))| (*dst, std::cmp::Reverse(*end)))
+        .unwrap();
+    (st, end, dst)
+}
+
+/// Label monomers in a sequence by tiling it with the best-fitting [`MonomerLibrary`] monomer on either strand.
+/// * Monomers are fit left to right. Each monomer starts at or after the end of the previous monomer.
+/// * Identity is the percent of the library monomer matched.
+/// * Regions where no monomer reaches [`AnnotationOpts::min_identity`] are skipped in steps of half a monomer.
+///
+/// # Args
+/// * `seq`
+///     * Sequence to annotate. ex. A read or an assembly region.
+/// * `library`
+///     * [`MonomerLibrary`] of monomer consensus sequences. Names that aren't valid [`Monomer`]s are skipped.
+/// * `opts`
+///     * [`AnnotationOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * Monomers as `(st, end, identity, monomer)` in order. See [`ContigMonomers`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{annotate_monomers, MonomerLibrary, Strand};
+///
+/// let fa = b">S1C1/5/19H1L.1\nACGTTGCAAGCTTACGGATC\n>S1C1/5/19H1L.2\nTTGACCGATAGGCTAACGTA\n";
+/// let library = MonomerLibrary::from_reader(&fa[..]).unwrap();
+/// let seq = b"ACGTTGCAAGCTTACGGATCTTGACCGATAGGCTAACGTA";
+/// let mons = annotate_monomers(seq, &library, None);
+/// assert_eq!(
+///     mons.iter().map(|(st, end, _, mon)| (*st, *end, mon.to_string())).collect::<Vec<_>>(),
+///     [(0, 20, "S1C1/5/19H1L.1".to_string()), (20, 40, "S1C1/5/19H1L.2".to_string())]
+/// );
+/// assert_eq!(mons[0].3.strand, Some(Strand::Plus));
+/// ```
+pub fn annotate_monomers(
+    seq: &[u8],
+    library: &MonomerLibrary,
+    opts: Option<AnnotationOpts>,
+) -> Vec<(u64, u64, f32, Monomer)> {
+    let opts = opts.unwrap_or_default();
+    // Sorted so ties between monomers are broken the same way on every run.
+    let templates = library
+        .iter()
+        .filter(|(_, mon_seq)| !mon_seq.is_empty())
+        .sorted_by_key(|(name, _)| *name)
+        .filter_map(|(name, mon_seq)| match Monomer::new(name) {
+            Ok(mon) => Some([
+                (mon.clone().with_strand(Strand::Plus), mon_seq.to_vec()),
+                (mon.with_strand(Strand::Minus), reverse_complement(mon_seq)),
+            ]),
+            Err(_) => {
+                log::error!("Cannot convert library monomer ({name}). Skipping.");
+                None
+            }
+        })
+        .flatten()
+        .collect_vec();
+    let Some(max_len) = templates.iter().map(|(_, mon_seq)| mon_seq.len()).max() else {
+        return vec![];
+    };
+    // Long enough to fit a monomer with some offset but not two consecutive monomers.
+    let window = max_len + max_len / 2;
+    let step = std::cmp::max(max_len / 2, 1);
+
+    let mut monomers = vec![];
+    let mut pos = 0;
+    while pos < seq.len() {
+        let target = &seq[pos..std::cmp::min(pos + window, seq.len())];
+        let (mon, (st, end, identity)) = templates
+            .iter()
+            .map(|(mon, mon_seq)| {
+                let (st, end, dst) = fit_alignment(mon_seq, target);
+                let identity = (1.0 - dst as f32 / mon_seq.len() as f32) * 100.0;
+                (mon, (st, end, identity))
+            })
+            .fold(
+                None,
+                |best: Option<(&Monomer, (usize, usize, f32))>, hit| match best {
+                    Some(best) if best.1 .2 >= hit.1 .2 => Some(best),
+                    _ => Some(hit),
+                },
+            )
+            .unwrap();
+        // Monomer may be truncated by the window so fit again from its start.
+        if st > 0 && end == target.len() && pos + end < seq.len() {
+            pos += st;
+            continue;
+        }
+        if identity < opts.min_identity || end == 0 {
+            pos += step;
+            continue;
+        }
+        monomers.push(((pos + st) as u64, (pos + end) as u64, identity, mon.clone()));
+        pos += end;
+    }
+    monomers
+}
+
+/// Annotate monomers in a [`Region`] of an assembly. See [`annotate_monomers`].
+///
+/// # Args
+/// * `fasta`
+///     * [`IndexedFasta`] of the assembly containing [`Region::name`].
+/// * `library`
+///     * [`MonomerLibrary`] of monomer consensus sequences.
+/// * `region`
+///     * [`Region`] to annotate.
+/// * `opts`
+///     * [`AnnotationOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`ContigMonomers`] keyed by the [`Region`] with coordinates relative to its start, as in HumAS-HMMER output.
+///     * Can be written with [`crate::write_to_monomer_bed`] or converted to StVs with [`crate::ChimeraCatalogue`], [`crate::StvIndex`], etc.
+pub fn annotate_region<R: Read + Seek>(
+    fasta: &mut IndexedFasta<R>,
+    library: &MonomerLibrary,
+    region: &Region,
+    opts: Option<AnnotationOpts>,
+) -> eyre::Result<ContigMonomers> {
+    let seq = fasta.fetch(&region.name, region.start, region.end)?;
+    let monomers = annotate_monomers(&seq, library, opts);
+    Ok(HashMap::from_iter([(region.to_string(), monomers)]))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::{
+        annotate_monomers, annotate_region, read_from_monomer_bed, write_to_monomer_bed,
+        AnnotationOpts, IndexedFasta, MonomerLibrary, Region, Strand,
+    };
+
+    use super::fit_alignment;
+
+    const MON_1: &str = "ACGTTGCAAGCTTACGGATC";
+    const MON_2: &str = "TTGACCGATAGGCTAACGTA";
+    // Reverse complement of MON_2.
+    const MON_2_RC: &str = "TACGTTAGCCTATCGGTCAA";
+
+    fn library() -> MonomerLibrary {
+        let fa = format!(">S1C1/5/19H1L.1\n{MON_1}\n>S1C1/5/19H1L.2\n{MON_2}\n");
+        MonomerLibrary::from_reader(fa.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_fit_alignment() {
+        assert_eq!(fit_alignment(b"CGTA", b"AACGTAGG"), (2, 6, 0));
+        assert_eq!(fit_alignment(b"CGTA", b"AACGAAGG"), (2, 6, 1));
+        assert_eq!(fit_alignment(b"cgta", b"CGTA"), (0, 4, 0));
+    }
+
+    #[test]
+    fn test_annotate_strands() {
+        // Junk, 1, 2 with a mismatch, then 2 and 1 on the minus strand.
+        let mon_2_mut = MON_2.replace("GATAGG", "GATTGG");
+        let seq = format!(
+            "GGGGGGGGGGGG{MON_1}{mon_2_mut}{MON_2_RC}{}",
+            String::from_utf8(crate::utils::reverse_complement(MON_1.as_bytes())).unwrap()
+        );
+        let mons = annotate_monomers(seq.as_bytes(), &library(), None);
+        assert_eq!(
+            mons.iter()
+                .map(|(st, end, identity, mon)| (
+                    *st,
+                    *end,
+                    *identity,
+                    mon.to_string(),
+                    mon.strand.unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (12, 32, 100.0, "S1C1/5/19H1L.1".to_owned(), Strand::Plus),
+                (32, 52, 95.0, "S1C1/5/19H1L.2".to_owned(), Strand::Plus),
+                (52, 72, 100.0, "S1C1/5/19H1L.2".to_owned(), Strand::Minus),
+                (72, 92, 100.0, "S1C1/5/19H1L.1".to_owned(), Strand::Minus),
+            ]
+        );
+
+        let strict = AnnotationOpts { min_identity: 99.0 };
+        let mons = annotate_monomers(seq.as_bytes(), &library(), Some(strict));
+        assert!(mons.iter().all(|(_, _, identity, _)| *identity == 100.0));
+        assert!(annotate_monomers(seq.as_bytes(), &MonomerLibrary::default(), None).is_empty());
+    }
+
+    #[test]
+    fn test_annotate_region_to_bed() {
+        let fa = format!(">chr1\nAAAA{MON_1}{MON_2}{MON_1}{MON_2}\n");
+        let fai = b"chr1\t84\t6\t84\t85\n";
+        let mut fasta = IndexedFasta::from_reader(Cursor::new(fa.into_bytes()), &fai[..]).unwrap();
+        let region = Region::new("chr1:4-84").unwrap();
+        let mons = annotate_region(&mut fasta, &library(), &region, None).unwrap();
+        assert_eq!(mons["chr1:4-84"].len(), 4);
+
+        // Annotations can be read back as StVs.
+        let mut output = vec![];
+        write_to_monomer_bed(&mut output, &mons).unwrap();
+        let bed = std::env::temp_dir().join("test_annotate_region_to_bed.bed");
+        std::fs::write(&bed, output).unwrap();
+        let records = read_from_monomer_bed(&bed, |_| false).unwrap();
+        std::fs::remove_file(&bed).unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|rec| (rec.chrom.as_str(), rec.st, rec.end, rec.hor.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                ("chr1:4-84", 0, 40, "S1C1/5/19H1L.1-2".to_owned()),
+                ("chr1:4-84", 40, 80, "S1C1/5/19H1L.1-2".to_owned()),
+            ]
+        );
+    }
+}
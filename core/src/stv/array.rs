@@ -0,0 +1,348 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::Status;
+
+use super::io::StvRecord;
+
+/// Options for [`find_hor_arrays`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayOpts {
+    /// Maximum distance in bp between two StVs of the same HOR to merge.
+    pub max_gap: u64,
+    /// Maximum length in bp of an interruption by StVs of other HORs between two StVs of the same HOR to merge.
+    pub max_interruption: u64,
+}
+
+impl Default for ArrayOpts {
+    fn default() -> Self {
+        Self {
+            max_gap: 1_000,
+            max_interruption: 2_000,
+        }
+    }
+}
+
+/// An array of StVs from the same HOR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HORArray {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    /// HOR name without monomer numbers. See [`crate::Monomer::hor_name`].
+    pub hor_name: String,
+    pub status: Option<Status>,
+    /// Number of StVs of this HOR in the array.
+    pub n_hor_copies: usize,
+    /// Number of monomers of this HOR in the array.
+    pub n_monomers: usize,
+    /// Number of StVs of other HORs merged into the array.
+    pub n_interruptions: usize,
+}
+
+impl HORArray {
+    /// Length of the array in bp.
+    pub fn len(&self) -> u64 {
+        self.end - self.st
+    }
+
+    /// Check if array has no length.
+    pub fn is_empty(&self) -> bool {
+        self.end == self.st
+    }
+
+    /// Array is composed of live HORs.
+    pub fn is_live(&self) -> bool {
+        self.status == Some(Status::Live)
+    }
+
+    /// Array is composed of divergent HORs.
+    pub fn is_divergent(&self) -> bool {
+        self.status == Some(Status::Divergent)
+    }
+}
+
+/// Label the array as in T2T censat tracks.
+/// * `active_hor` for live, `dhor` for divergent, and `hor` otherwise.
+impl Display for HORArray {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.status {
+            Some(Status::Live) => "active_hor",
+            Some(Status::Divergent) => "dhor",
+            None => "hor",
+        };
+        write!(f, "{label}({})", self.hor_name)
+    }
+}
+
+/// Merge consecutive [`StvRecord`]s of the same HOR into [`HORArray`]s.
+/// * StVs are grouped by HOR name, including status. See [`crate::Monomer::hor_name`].
+/// * Records of different contigs are never merged. Records are sorted by position first.
+///
+/// # Args
+/// * `records`
+///     * StVs to merge.
+/// * `opts`
+///     * [`ArrayOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`HORArray`]s sorted by contig and position.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{find_hor_arrays, StvRecord, HOR};
+///
+/// let live = HOR::new("S2C15H1L.11-1").unwrap();
+/// let div = HOR::new("S2C15H3d.1-4").unwrap();
+/// let records = [
+///     StvRecord::new("chr15", 0, 680, div.clone()),
+///     StvRecord::new("chr15", 680, 2550, live.clone()),
+///     StvRecord::new("chr15", 2550, 4420, live.clone()),
+///     StvRecord::new("chr15", 4420, 5100, div.clone()),
+/// ];
+/// let arrays = find_hor_arrays(&records, None);
+/// assert_eq!(
+///     arrays.iter().map(|arr| arr.to_string()).collect::<Vec<_>>(),
+///     ["dhor(S2C15H3d)", "active_hor(S2C15H1L)", "dhor(S2C15H3d)"]
+/// );
+/// assert_eq!(arrays[1].n_hor_copies, 2);
+/// ```
+pub fn find_hor_arrays(records: &[StvRecord], opts: Option<ArrayOpts>) -> Vec<HORArray> {
+    let opts = opts.unwrap_or_default();
+    let records = records
+        .iter()
+        .filter_map(|rec| Some((rec, rec.hor.first()?)))
+        .sorted_by(|(a, _), (b, _)| (&a.chrom, a.st).cmp(&(&b.chrom, b.st)))
+        .collect_vec();
+
+    let mut arrays: Vec<HORArray> = vec![];
+    let mut idx = 0;
+    while let Some((rec, mon)) = records.get(idx) {
+        let mut array = HORArray {
+            chrom: rec.chrom.clone(),
+            st: rec.st,
+            end: rec.end,
+            hor_name: mon.hor_name(),
+            status: mon.status.clone(),
+            n_hor_copies: 1,
+            n_monomers: rec.hor.n_monomers(),
+            n_interruptions: 0,
+        };
+        idx += 1;
+
+        while let Some((next_rec, next_mon)) = records.get(idx) {
+            if next_rec.chrom != array.chrom {
+                break;
+            }
+            if next_mon.hor_name() == array.hor_name {
+                if next_rec.st.saturating_sub(array.end) > opts.max_gap {
+                    break;
+                }
+                array.end = std::cmp::max(array.end, next_rec.end);
+                array.n_hor_copies += 1;
+                array.n_monomers += next_rec.hor.n_monomers();
+                idx += 1;
+                continue;
+            }
+            // Check if interrupted by other HORs and the array continues after.
+            let Some(n_interruptions) = records[idx..]
+                .iter()
+                .take_while(|(itr_rec, _)| {
+                    itr_rec.chrom == array.chrom
+                        && itr_rec.st.saturating_sub(array.end) <= opts.max_interruption
+                })
+                .position(|(_, itr_mon)| itr_mon.hor_name() == array.hor_name)
+            else {
+                break;
+            };
+            for (itr_rec, _) in &records[idx..idx + n_interruptions] {
+                array.end = std::cmp::max(array.end, itr_rec.end);
+            }
+            array.n_interruptions += n_interruptions;
+            idx += n_interruptions;
+        }
+        arrays.push(array);
+    }
+    arrays
+}
+
+/// Divergent regions flanking a [`HORArray`] as `(st, end)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArrayFlanks {
+    pub left: Option<(u64, u64)>,
+    pub right: Option<(u64, u64)>,
+}
+
+/// Get the divergent regions flanking an array.
+/// * Consecutive divergent arrays directly before and after the array within `max_gap` are merged.
+///
+/// # Args
+/// * `arrays`
+///     * [`HORArray`]s sorted by contig and position. See [`find_hor_arrays`].
+/// * `idx`
+///     * Index of array in `arrays`.
+/// * `max_gap`
+///     * Maximum distance in bp between arrays.
+///
+/// # Returns
+/// * Left and right flanking divergent regions as [`ArrayFlanks`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{divergent_flanks, find_hor_arrays, StvRecord, HOR};
+///
+/// let live = HOR::new("S2C15H1L.11-1").unwrap();
+/// let div = HOR::new("S2C15H3d.1-4").unwrap();
+/// let records = [
+///     StvRecord::new("chr15", 0, 680, div.clone()),
+///     StvRecord::new("chr15", 680, 2550, live.clone()),
+/// ];
+/// let arrays = find_hor_arrays(&records, None);
+/// let flanks = divergent_flanks(&arrays, 1, 1_000);
+/// assert_eq!((flanks.left, flanks.right), (Some((0, 680)), None));
+/// ```
+pub fn divergent_flanks(arrays: &[HORArray], idx: usize, max_gap: u64) -> ArrayFlanks {
+    let Some(array) = arrays.get(idx) else {
+        return ArrayFlanks::default();
+    };
+    let fn_flank = |flanks: &mut dyn Iterator<Item = &HORArray>| {
+        let mut region: Option<(u64, u64)> = None;
+        let (mut prev_st, mut prev_end) = (array.st, array.end);
+        for flank in flanks {
+            let dst = if flank.end <= prev_st {
+                prev_st - flank.end
+            } else {
+                flank.st.saturating_sub(prev_end)
+            };
+            if flank.chrom != array.chrom || !flank.is_divergent() || dst > max_gap {
+                break;
+            }
+            region = Some(match region {
+                Some((st, end)) => (st.min(flank.st), end.max(flank.end)),
+                None => (flank.st, flank.end),
+            });
+            (prev_st, prev_end) = (flank.st, flank.end);
+        }
+        region
+    };
+    ArrayFlanks {
+        left: fn_flank(&mut arrays[..idx].iter().rev()),
+        right: fn_flank(&mut arrays[idx + 1..].iter()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::{
+        divergent_flanks, find_hor_arrays, stv::test_utils::records, ArrayFlanks, ArrayOpts,
+    };
+
+    #[test]
+    fn test_arrays_gap() {
+        let records = records(&[
+            ("chr1", 0, 100, "S1C1/5/19H1L.1-6"),
+            ("chr1", 100, 200, "S1C1/5/19H1L.1-6"),
+            // Gap of 500 bp.
+            ("chr1", 700, 800, "S1C1/5/19H1L.1-6"),
+        ]);
+        let opts = ArrayOpts {
+            max_gap: 100,
+            max_interruption: 100,
+        };
+        let arrays = find_hor_arrays(&records, Some(opts));
+        assert_eq!(
+            arrays
+                .iter()
+                .map(|arr| (arr.st, arr.end, arr.n_hor_copies, arr.n_monomers))
+                .collect_vec(),
+            [(0, 200, 2, 12), (700, 800, 1, 6)]
+        );
+
+        let arrays = find_hor_arrays(&records, None);
+        assert_eq!(
+            arrays
+                .iter()
+                .map(|arr| (arr.st, arr.end, arr.n_hor_copies, arr.n_monomers))
+                .collect_vec(),
+            [(0, 800, 3, 18)]
+        );
+    }
+
+    #[test]
+    fn test_arrays_interruption() {
+        let records = records(&[
+            ("chr1", 0, 100, "S1C1/5/19H1L.1-6"),
+            ("chr1", 100, 150, "S1C1/5/19H2.1-2"),
+            ("chr1", 150, 250, "S1C1/5/19H1L.1-6"),
+            ("chr1", 250, 2000, "S1C1/5/19H2.1-2"),
+            ("chr1", 2000, 2100, "S1C1/5/19H1L.1-6"),
+        ]);
+        let opts = ArrayOpts {
+            max_gap: 100,
+            max_interruption: 100,
+        };
+        let arrays = find_hor_arrays(&records, Some(opts));
+        assert_eq!(
+            arrays
+                .iter()
+                .map(|arr| (arr.to_string(), arr.st, arr.end, arr.n_interruptions))
+                .collect_vec(),
+            [
+                (String::from("active_hor(S1C1/5/19H1L)"), 0, 250, 1),
+                (String::from("hor(S1C1/5/19H2)"), 250, 2000, 0),
+                (String::from("active_hor(S1C1/5/19H1L)"), 2000, 2100, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrays_unsorted_contigs() {
+        let records = records(&[
+            ("chr2", 0, 100, "S1C1/5/19H1L.1-6"),
+            ("chr1", 100, 200, "S1C1/5/19H1L.1-6"),
+            ("chr1", 0, 100, "S1C1/5/19H1L.1-6"),
+        ]);
+        let arrays = find_hor_arrays(&records, None);
+        assert_eq!(
+            arrays
+                .iter()
+                .map(|arr| (arr.chrom.as_str(), arr.st, arr.end))
+                .collect_vec(),
+            [("chr1", 0, 200), ("chr2", 0, 100)]
+        );
+    }
+
+    #[test]
+    fn test_divergent_flanks() {
+        let records = records(&[
+            ("chr1", 0, 100, "S1C1/5/19H3d.1-2"),
+            ("chr1", 100, 200, "S1C1/5/19H4d.1-2"),
+            ("chr1", 200, 300, "S1C1/5/19H1L.1-6"),
+            ("chr1", 5000, 5100, "S1C1/5/19H3d.1-2"),
+        ]);
+        let opts = ArrayOpts {
+            max_gap: 100,
+            max_interruption: 100,
+        };
+        let arrays = find_hor_arrays(&records, Some(opts));
+        assert_eq!(arrays.len(), 4);
+        assert!(arrays[2].is_live());
+        assert_eq!(
+            divergent_flanks(&arrays, 2, 100),
+            ArrayFlanks {
+                left: Some((0, 200)),
+                right: None
+            }
+        );
+        assert_eq!(
+            divergent_flanks(&arrays, 2, 5000),
+            ArrayFlanks {
+                left: Some((0, 200)),
+                right: Some((5000, 5100))
+            }
+        );
+    }
+}
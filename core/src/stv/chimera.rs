@@ -0,0 +1,270 @@
+use std::{collections::HashMap, fmt::Display};
+
+use itertools::Itertools;
+
+use crate::{Monomer, HOR};
+
+use super::{
+    array::HORArray,
+    io::{monomers_to_stv_records, ContigMonomers, StvRecord},
+};
+
+/// A junction between the parent monomers of a chimeric monomer.
+/// * ex. `S1C1/5/19H1L.6/4`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Junction {
+    /// HOR name without monomer numbers. See [`crate::Monomer::hor_name`].
+    pub hor_name: String,
+    /// Parent monomers in order of the label.
+    pub parents: Vec<u8>,
+}
+
+impl Display for Junction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.hor_name, self.parents.iter().join("/"))
+    }
+}
+
+/// A chimeric monomer and the StV containing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChimericMonomer {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    pub monomer: Monomer,
+    /// StV containing the chimeric monomer.
+    pub stv: StvRecord,
+}
+
+impl ChimericMonomer {
+    /// Parent monomers of the chimeric monomer.
+    pub fn parents(&self) -> &[u8] {
+        &self.monomer.monomers
+    }
+
+    /// [`Junction`] type of the chimeric monomer.
+    pub fn junction(&self) -> Junction {
+        Junction {
+            hor_name: self.monomer.hor_name(),
+            parents: self.monomer.monomers.clone(),
+        }
+    }
+}
+
+/// A [`Junction`] shared by multiple copies of the same StV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrentJunction {
+    pub junction: Junction,
+    /// StV family the junction is found in.
+    pub stv: HOR,
+    /// Number of chimeric monomers with this junction in the StV family.
+    pub n_copies: usize,
+}
+
+/// A catalogue of chimeric monomers.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use rs_asat_hor::{ChimeraCatalogue, Monomer};
+///
+/// let mons = ["S1C1/5/19H1L.1", "S1C1/5/19H1L.2/4", "S1C1/5/19H1L.5", "S1C1/5/19H1L.1", "S1C1/5/19H1L.2/4", "S1C1/5/19H1L.5"]
+///     .iter()
+///     .enumerate()
+///     .map(|(i, mon)| (i as u64 * 171, (i as u64 + 1) * 171, 100.0, Monomer::new(mon).unwrap()))
+///     .collect();
+/// let monomers = HashMap::from_iter([(String::from("chr1"), mons)]);
+/// let catalogue = ChimeraCatalogue::new(&monomers).unwrap();
+/// assert_eq!(catalogue.chimeras().len(), 2);
+///
+/// let recurrent = catalogue.recurrent_junctions(2);
+/// assert_eq!(recurrent[0].junction.to_string(), "S1C1/5/19H1L.2/4");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChimeraCatalogue {
+    chimeras: Vec<ChimericMonomer>,
+}
+
+impl ChimeraCatalogue {
+    /// Collect all chimeric monomers with the StVs containing them.
+    ///
+    /// # Args
+    /// * `monomers`
+    ///     * [`ContigMonomers`] from [`crate::read_monomers_from_bed`].
+    ///
+    /// # Returns
+    /// * [`ChimeraCatalogue`] with chimeric monomers sorted by contig and position.
+    pub fn new(monomers: &ContigMonomers) -> eyre::Result<Self> {
+        let mut chimeras = vec![];
+        for (chrom, mons) in monomers.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+            let records = monomers_to_stv_records(chrom, mons)?;
+            // Each StV consumes its number of monomers in order.
+            let stv_mons = records
+                .iter()
+                .flat_map(|rec| std::iter::repeat_n(rec, rec.hor.n_monomers()));
+            for ((st, end, _, mon), stv) in mons.iter().zip(stv_mons) {
+                if !mon.is_chimeric() {
+                    continue;
+                }
+                chimeras.push(ChimericMonomer {
+                    chrom: chrom.to_owned(),
+                    st: *st,
+                    end: *end,
+                    monomer: mon.clone(),
+                    stv: stv.clone(),
+                });
+            }
+        }
+        chimeras.sort_by(|a, b| (&a.chrom, a.st).cmp(&(&b.chrom, b.st)));
+        Ok(Self { chimeras })
+    }
+
+    /// Get all chimeric monomers.
+    pub fn chimeras(&self) -> &[ChimericMonomer] {
+        &self.chimeras
+    }
+
+    /// Count the [`Junction`]s within each array.
+    ///
+    /// # Args
+    /// * `arrays`
+    ///     * [`HORArray`]s. See [`crate::find_hor_arrays`].
+    ///
+    /// # Returns
+    /// * [`Junction`] counts for each array in order of `arrays`, sorted by count.
+    pub fn junction_counts(&self, arrays: &[HORArray]) -> Vec<Vec<(Junction, usize)>> {
+        arrays
+            .iter()
+            .map(|array| {
+                self.chimeras
+                    .iter()
+                    .filter(|chimera| {
+                        chimera.chrom == array.chrom
+                            && chimera.st >= array.st
+                            && chimera.end <= array.end
+                    })
+                    .map(|chimera| chimera.junction())
+                    .counts()
+                    .into_iter()
+                    .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Get [`Junction`]s found in at least `min_copies` copies of the same StV.
+    /// * Recurrent junctions define StV families where a recombination event was propagated.
+    ///
+    /// # Args
+    /// * `min_copies`
+    ///     * Minimum number of chimeric monomers with the same junction in the same StV.
+    ///
+    /// # Returns
+    /// * [`RecurrentJunction`]s sorted by number of copies.
+    pub fn recurrent_junctions(&self, min_copies: usize) -> Vec<RecurrentJunction> {
+        let mut counts: HashMap<(Junction, String), (&HOR, usize)> = HashMap::new();
+        for chimera in self.chimeras.iter() {
+            counts
+                .entry((chimera.junction(), chimera.stv.hor.normalized().to_string()))
+                .or_insert((&chimera.stv.hor, 0))
+                .1 += 1;
+        }
+        counts
+            .into_iter()
+            .filter(|(_, (_, n_copies))| *n_copies >= min_copies)
+            .sorted_by(
+                |((junction_a, stv_a), (_, n_a)), ((junction_b, stv_b), (_, n_b))| {
+                    n_b.cmp(n_a)
+                        .then_with(|| junction_a.cmp(junction_b))
+                        .then_with(|| stv_a.cmp(stv_b))
+                },
+            )
+            .map(|((junction, _), (stv, n_copies))| RecurrentJunction {
+                junction,
+                stv: stv.clone(),
+                n_copies,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::{find_hor_arrays, stv::test_utils::contig_monomers, ChimeraCatalogue};
+
+    #[test]
+    fn test_chimeras() {
+        let mons = contig_monomers(&[(
+            "chr1",
+            &["1", "2", "3/5", "6", "1", "2", "3/5", "6", "1", "2", "6/4"],
+        )]);
+        let catalogue = ChimeraCatalogue::new(&mons).unwrap();
+
+        let chimeras = catalogue.chimeras();
+        assert_eq!(chimeras.len(), 3);
+        assert_eq!(
+            chimeras
+                .iter()
+                .map(|c| (c.st, c.end, c.parents().to_vec(), c.stv.hor.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (342, 513, vec![3, 5], String::from("S1C1/5/19H1L.1-2_3/5_6")),
+                (
+                    1026,
+                    1197,
+                    vec![3, 5],
+                    String::from("S1C1/5/19H1L.1-2_3/5_6")
+                ),
+                (1710, 1881, vec![6, 4], String::from("S1C1/5/19H1L.6/4")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recurrent_junctions() {
+        let mons = contig_monomers(&[(
+            "chr1",
+            &["1", "2", "3/5", "6", "1", "2", "3/5", "6", "1", "2", "6/4"],
+        )]);
+        let catalogue = ChimeraCatalogue::new(&mons).unwrap();
+
+        let recurrent = catalogue.recurrent_junctions(2);
+        assert_eq!(recurrent.len(), 1);
+        assert_eq!(recurrent[0].junction.to_string(), "S1C1/5/19H1L.3/5");
+        assert_eq!(recurrent[0].stv.to_string(), "S1C1/5/19H1L.1-2_3/5_6");
+        assert_eq!(recurrent[0].n_copies, 2);
+
+        assert_eq!(catalogue.recurrent_junctions(1).len(), 2);
+    }
+
+    #[test]
+    fn test_junction_counts() {
+        let mons = contig_monomers(&[(
+            "chr1",
+            &["1", "2", "3/5", "6", "1", "2", "3/5", "6", "1", "2", "6/4"],
+        )]);
+        let catalogue = ChimeraCatalogue::new(&mons).unwrap();
+        let records = catalogue
+            .chimeras()
+            .iter()
+            .map(|chimera| chimera.stv.clone())
+            .dedup()
+            .collect_vec();
+        let arrays = find_hor_arrays(&records, None);
+
+        let counts = catalogue.junction_counts(&arrays);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(
+            counts[0]
+                .iter()
+                .map(|(junction, n)| (junction.to_string(), *n))
+                .collect::<Vec<_>>(),
+            [
+                (String::from("S1C1/5/19H1L.3/5"), 2),
+                (String::from("S1C1/5/19H1L.6/4"), 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,547 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, Write},
+};
+
+use itertools::Itertools;
+
+use crate::{utils::edit_distance, Strand};
+
+use super::{array::HORArray, fasta::IndexedFasta, io::StvRecord};
+
+/// Options for [`hor_copy_divergence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivergenceOpts {
+    /// Number of neighboring copies of the same StV on each side to compare with pairwise.
+    pub n_neighbors: usize,
+}
+
+impl Default for DivergenceOpts {
+    fn default() -> Self {
+        Self { n_neighbors: 2 }
+    }
+}
+
+/// Sequence divergence of a single StV copy.
+/// * Divergence is the edit distance as a percentage of the longer sequence.
+/// * A divergence is `None` if there are no other copies to compare with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StvDivergence<'a> {
+    pub stv: &'a StvRecord,
+    /// Index of the [`HORArray`] containing the StV.
+    pub array_idx: usize,
+    /// Number of copies of the same StV in the array, including this one.
+    pub n_copies: usize,
+    /// Divergence from the consensus of all copies of the same StV in the array.
+    pub consensus_divergence: Option<f32>,
+    /// Mean pairwise divergence from the nearest copies of the same StV in the array.
+    pub pairwise_divergence: Option<f32>,
+    /// Divergence from the consensus of copies of the same StV in all other arrays.
+    pub between_array_divergence: Option<f32>,
+}
+
+/// A StV and its sequence.
+type StvCopy<'a> = (&'a StvRecord, Vec<u8>);
+
+/// Percent divergence between two sequences. Case is ignored.
+fn divergence(seq_1: &[u8], seq_2: &[u8]) -> f32 {
+    let max_len = std::cmp::max(seq_1.len(), seq_2.len());
+    if max_len == 0 {
+        return 0.0;
+    }
+    let dst = edit_distance(seq_1, seq_2, |a, b| a.eq_ignore_ascii_case(b));
+    dst as f32 / max_len as f32 * 100.0
+}
+
+/// Direction of a step in the traceback of [`align_to_reference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Diagonal = 0,
+    Deletion = 1,
+    Insertion = 2,
+}
+
+/// Traceback of [`align_to_reference`] with each [`Step`] packed into 2 bits.
+struct Traceback {
+    steps: Vec<u8>,
+    n_cols: usize,
+}
+
+impl Traceback {
+    fn new(n_rows: usize, n_cols: usize) -> Self {
+        Self {
+            steps: vec![0; (n_rows * n_cols).div_ceil(4)],
+            n_cols,
+        }
+    }
+
+    fn set(&mut self, i: usize, j: usize, step: Step) {
+        let idx = i * self.n_cols + j;
+        self.steps[idx / 4] |= (step as u8) << (idx % 4 * 2);
+    }
+
+    fn get(&self, i: usize, j: usize) -> Step {
+        let idx = i * self.n_cols + j;
+        match (self.steps[idx / 4] >> (idx % 4 * 2)) & 0b11 {
+            0 => Step::Diagonal,
+            1 => Step::Deletion,
+            _ => Step::Insertion,
+        }
+    }
+}
+
+/// Align `seq` globally to `reference` and get the aligned base, or `None` for a deletion, at each reference position.
+/// * Insertions relative to the reference are dropped.
+/// * Keeps two rows of edit distances and a 2-bit traceback, so memory is `O(n * m / 4)` bytes rather than `O(n * m)` words.
+fn align_to_reference(reference: &[u8], seq: &[u8]) -> Vec<Option<u8>> {
+    let (n, m) = (reference.len(), seq.len());
+    let mut traceback = Traceback::new(n + 1, m + 1);
+    let mut prev_row = (0..=m).collect_vec();
+    let mut row = vec![0; m + 1];
+    for j in 1..=m {
+        traceback.set(0, j, Step::Insertion);
+    }
+    for i in 1..=n {
+        row[0] = i;
+        traceback.set(i, 0, Step::Deletion);
+        for j in 1..=m {
+            let cost = usize::from(!reference[i - 1].eq_ignore_ascii_case(&seq[j - 1]));
+            let diagonal = prev_row[j - 1] + cost;
+            let deletion = prev_row[j] + 1;
+            let insertion = row[j - 1] + 1;
+            // Prefer diagonals then deletions on ties.
+            let (dst, step) = if diagonal <= deletion && diagonal <= insertion {
+                (diagonal, Step::Diagonal)
+            } else if deletion <= insertion {
+                (deletion, Step::Deletion)
+            } else {
+                (insertion, Step::Insertion)
+            };
+            row[j] = dst;
+            traceback.set(i, j, step);
+        }
+        std::mem::swap(&mut prev_row, &mut row);
+    }
+
+    let mut aligned = vec![None; n];
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        match traceback.get(i, j) {
+            Step::Diagonal => {
+                aligned[i - 1] = Some(seq[j - 1].to_ascii_uppercase());
+                (i, j) = (i - 1, j - 1);
+            }
+            Step::Deletion => i -= 1,
+            Step::Insertion => j -= 1,
+        }
+    }
+    aligned
+}
+
+/// Counts of each aligned base, or `None` for a deletion, at each position of a consensus reference.
+type BaseCounts = Vec<HashMap<Option<u8>, usize>>;
+
+/// Get the sequence of median length to align sequences to when building a consensus.
+fn consensus_reference<'a>(seqs: impl IntoIterator<Item = &'a [u8]>) -> Option<&'a [u8]> {
+    let seqs = seqs
+        .into_iter()
+        .sorted_by_key(|seq| seq.len())
+        .collect_vec();
+    seqs.get(seqs.len() / 2).copied()
+}
+
+/// Add the bases of a sequence aligned with [`align_to_reference`] to `counts`.
+fn add_base_counts(counts: &mut BaseCounts, aligned: Vec<Option<u8>>) {
+    for (count, base) in counts.iter_mut().zip(aligned) {
+        *count.entry(base).or_default() += 1;
+    }
+}
+
+/// Get the majority base at each position of `counts`.
+/// * Positions where a deletion is the majority are dropped.
+fn majority<'a>(counts: impl IntoIterator<Item = &'a HashMap<Option<u8>, usize>>) -> Vec<u8> {
+    counts
+        .into_iter()
+        .filter_map(|count| {
+            // Break ties deterministically, preferring bases.
+            count
+                .iter()
+                .filter(|(_, n)| **n > 0)
+                .max_by_key(|(base, n)| (**n, **base))
+                .and_then(|(base, _)| *base)
+        })
+        .collect()
+}
+
+/// Build a majority consensus of sequences by aligning each to the sequence of median length.
+/// * Positions where a deletion is the majority are dropped.
+#[cfg(test)]
+fn consensus(seqs: &[&[u8]]) -> Vec<u8> {
+    let Some(reference) = consensus_reference(seqs.iter().copied()) else {
+        return vec![];
+    };
+    let mut counts: BaseCounts = vec![HashMap::new(); reference.len()];
+    for seq in seqs {
+        add_base_counts(&mut counts, align_to_reference(reference, seq));
+    }
+    majority(&counts)
+}
+
+/// Assign each record to the [`HORArray`] of its HOR containing it.
+/// * Records and arrays are swept once in order of position. Arrays of the same HOR on a contig don't overlap.
+///
+/// # Returns
+/// * Index of the array of each record in order of `records`, or `None` if not in an array of its HOR.
+fn assign_arrays(records: &[&StvRecord], arrays: &[HORArray]) -> Vec<Option<usize>> {
+    // Arrays of each contig and HOR sorted by position, with a cursor to the first array not before the current record.
+    let mut groups: HashMap<&str, HashMap<&str, (Vec<usize>, usize)>> = HashMap::new();
+    for (idx, array) in arrays.iter().enumerate() {
+        groups
+            .entry(&array.chrom)
+            .or_default()
+            .entry(&array.hor_name)
+            .or_default()
+            .0
+            .push(idx);
+    }
+    for (idxs, _) in groups.values_mut().flat_map(|hors| hors.values_mut()) {
+        idxs.sort_by_key(|idx| arrays[*idx].st);
+    }
+    records
+        .iter()
+        .map(|rec| {
+            let hor_name = rec.hor.first()?.hor_name();
+            let (idxs, cursor) = groups
+                .get_mut(rec.chrom.as_str())?
+                .get_mut(hor_name.as_str())?;
+            // Records are sorted by start so arrays ending before this one can't contain later records.
+            while idxs
+                .get(*cursor)
+                .is_some_and(|idx| arrays[*idx].end <= rec.st)
+            {
+                *cursor += 1;
+            }
+            let idx = *idxs.get(*cursor)?;
+            let array = &arrays[idx];
+            (array.st <= rec.st && rec.end <= array.end).then_some(idx)
+        })
+        .collect()
+}
+
+/// Key of a StV independent of its strand so copies in opposite orientations are grouped.
+fn stv_key(rec: &StvRecord) -> String {
+    match rec.strand() {
+        Some(Strand::Minus) => rec.hor.reversed().normalized().to_string(),
+        Some(Strand::Plus) | None => rec.hor.normalized().to_string(),
+    }
+}
+
+/// Calculate the sequence divergence of each StV copy from other copies of the same StV.
+/// * Copies are compared within their [`HORArray`] and between arrays to distinguish homogenized and older regions.
+/// * Sequences are compared in the orientation of the HOR. Minus strand StVs are reverse complemented.
+/// * StVs not contained in an array of their HOR are skipped.
+///
+/// # Args
+/// * `records`
+///     * [`StvRecord`]s. See [`crate::read_from_monomer_bed`].
+/// * `arrays`
+///     * [`HORArray`]s of `records`. See [`crate::find_hor_arrays`].
+/// * `fasta`
+///     * [`IndexedFasta`] of the assembly.
+/// * `opts`
+///     * [`DivergenceOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`StvDivergence`] for each StV copy by array and position.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use rs_asat_hor::{find_hor_arrays, hor_copy_divergence, IndexedFasta, StvRecord, HOR};
+///
+/// let fa = b">chr1\nACGTACGTACGAACGT\n";
+/// let fai = b"chr1\t16\t6\t16\t17\n";
+/// let mut fasta = IndexedFasta::from_reader(Cursor::new(fa), &fai[..]).unwrap();
+/// let hor = HOR::new("S1C1/5/19H1L.1-2").unwrap();
+/// let records: Vec<StvRecord> = (0..4)
+///     .map(|i| StvRecord::new("chr1", i * 4, (i + 1) * 4, hor.clone()))
+///     .collect();
+/// let arrays = find_hor_arrays(&records, None);
+/// let divs = hor_copy_divergence(&records, &arrays, &mut fasta, None).unwrap();
+/// assert_eq!(divs[2].consensus_divergence, Some(25.0));
+/// assert_eq!(divs[0].consensus_divergence, Some(0.0));
+/// ```
+pub fn hor_copy_divergence<'a, R: Read + Seek>(
+    records: &'a [StvRecord],
+    arrays: &[HORArray],
+    fasta: &mut IndexedFasta<R>,
+    opts: Option<DivergenceOpts>,
+) -> eyre::Result<Vec<StvDivergence<'a>>> {
+    let opts = opts.unwrap_or_default();
+    let records = records
+        .iter()
+        .sorted_by(|a, b| (&a.chrom, a.st).cmp(&(&b.chrom, b.st)))
+        .collect_vec();
+
+    // Group copies by StV and array.
+    let mut copies: HashMap<String, Vec<Vec<StvCopy>>> = HashMap::new();
+    for (rec, array_idx) in records.iter().zip(assign_arrays(&records, arrays)) {
+        let Some(array_idx) = array_idx else {
+            continue;
+        };
+        let seq = fasta.fetch_stv(rec)?;
+        copies
+            .entry(stv_key(rec))
+            .or_insert_with(|| vec![vec![]; arrays.len()])[array_idx]
+            .push((rec, seq));
+    }
+
+    let mut divergences: Vec<Vec<StvDivergence>> = vec![vec![]; arrays.len()];
+    for stv_copies in copies.values() {
+        // Align every copy once to a reference shared by all arrays.
+        // Consensuses of other arrays are the total counts less those of the array.
+        let Some(reference) =
+            consensus_reference(stv_copies.iter().flatten().map(|(_, seq)| seq.as_slice()))
+        else {
+            continue;
+        };
+        let mut total_counts: BaseCounts = vec![HashMap::new(); reference.len()];
+        let array_counts = stv_copies
+            .iter()
+            .map(|array_copies| {
+                let mut counts: BaseCounts = vec![HashMap::new(); reference.len()];
+                for (_, seq) in array_copies {
+                    let aligned = align_to_reference(reference, seq);
+                    add_base_counts(&mut total_counts, aligned.clone());
+                    add_base_counts(&mut counts, aligned);
+                }
+                counts
+            })
+            .collect_vec();
+        let n_total = stv_copies
+            .iter()
+            .map(|array_copies| array_copies.len())
+            .sum::<usize>();
+
+        for (array_idx, (array_copies, counts)) in stv_copies.iter().zip(&array_counts).enumerate()
+        {
+            let n_copies = array_copies.len();
+            if n_copies == 0 {
+                continue;
+            }
+            let array_consensus = (n_copies > 1).then(|| majority(counts));
+            let other_consensus = (n_total > n_copies).then(|| {
+                let other_counts = total_counts
+                    .iter()
+                    .zip(counts)
+                    .map(|(total, count)| {
+                        total
+                            .iter()
+                            .map(|(base, n)| (*base, n - count.get(base).unwrap_or(&0)))
+                            .collect()
+                    })
+                    .collect_vec();
+                majority(&other_counts)
+            });
+
+            for (i, (rec, seq)) in array_copies.iter().enumerate() {
+                let neighbors = array_copies
+                    .iter()
+                    .enumerate()
+                    .skip(i.saturating_sub(opts.n_neighbors))
+                    .take_while(|(j, _)| *j <= i + opts.n_neighbors)
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, (_, other))| divergence(seq, other))
+                    .collect_vec();
+                divergences[array_idx].push(StvDivergence {
+                    stv: rec,
+                    array_idx,
+                    n_copies,
+                    consensus_divergence: array_consensus
+                        .as_ref()
+                        .map(|cons| divergence(seq, cons)),
+                    pairwise_divergence: (!neighbors.is_empty())
+                        .then(|| neighbors.iter().sum::<f32>() / neighbors.len() as f32),
+                    between_array_divergence: other_consensus
+                        .as_ref()
+                        .map(|cons| divergence(seq, cons)),
+                });
+            }
+        }
+    }
+    Ok(divergences
+        .into_iter()
+        .flat_map(|mut array_divergences| {
+            array_divergences.sort_by_key(|div| div.stv.st);
+            array_divergences
+        })
+        .collect())
+}
+
+/// Write [`StvDivergence`]s to a `BED4+4` file.
+/// * Columns are `chrom`, `st`, `end`, `stv`, `n_copies`, `consensus_divergence`, `pairwise_divergence`, and `between_array_divergence`.
+/// * Missing divergences are written as `.`.
+pub fn write_to_divergence_bed<'a>(
+    mut output: impl Write,
+    divergences: impl IntoIterator<Item = &'a StvDivergence<'a>>,
+) -> eyre::Result<()> {
+    let fn_fmt = |div: Option<f32>| div.map_or(String::from("."), |div| format!("{div:.2}"));
+    for div in divergences {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            div.stv.chrom,
+            div.stv.st,
+            div.stv.end,
+            div.stv.hor,
+            div.n_copies,
+            fn_fmt(div.consensus_divergence),
+            fn_fmt(div.pairwise_divergence),
+            fn_fmt(div.between_array_divergence),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::{
+        find_hor_arrays, hor_copy_divergence, write_to_divergence_bed, DivergenceOpts, HORArray,
+        IndexedFasta, StvRecord, HOR,
+    };
+
+    use super::{align_to_reference, assign_arrays, consensus};
+
+    #[test]
+    fn test_align_to_reference() {
+        assert_eq!(
+            align_to_reference(b"ACGT", b"AGT"),
+            [Some(b'A'), None, Some(b'G'), Some(b'T')]
+        );
+        // Insertion dropped.
+        assert_eq!(
+            align_to_reference(b"ACGT", b"acCgt"),
+            [Some(b'A'), Some(b'C'), Some(b'G'), Some(b'T')]
+        );
+        assert_eq!(
+            align_to_reference(b"ACGTA", b"ACTTA"),
+            [Some(b'A'), Some(b'C'), Some(b'T'), Some(b'T'), Some(b'A')]
+        );
+        assert_eq!(align_to_reference(b"AC", b""), [None, None]);
+        assert!(align_to_reference(b"", b"AC").is_empty());
+    }
+
+    #[test]
+    fn test_assign_arrays() {
+        let fn_array = |chrom: &str, st: u64, end: u64, hor_name: &str| HORArray {
+            chrom: chrom.to_owned(),
+            st,
+            end,
+            hor_name: hor_name.to_owned(),
+            status: None,
+            n_hor_copies: 1,
+            n_monomers: 1,
+            n_interruptions: 0,
+        };
+        let arrays = [
+            fn_array("chr1", 200, 300, "S1C1/5/19H1L"),
+            fn_array("chr1", 100, 200, "S1C1/5/19H2"),
+            fn_array("chr1", 0, 100, "S1C1/5/19H1L"),
+        ];
+        let records = [
+            ("chr1", 10, 20, "S1C1/5/19H1L.1-2"),
+            ("chr1", 120, 130, "S1C1/5/19H2.1-2"),
+            // Not in an array of its HOR.
+            ("chr1", 150, 160, "S1C1/5/19H1L.1-2"),
+            ("chr1", 250, 260, "S1C1/5/19H1L.1-2"),
+            // Past the end of the array.
+            ("chr1", 290, 310, "S1C1/5/19H1L.1-2"),
+            ("chr2", 10, 20, "S1C1/5/19H1L.1-2"),
+        ]
+        .map(|(chrom, st, end, hor)| StvRecord::new(chrom, st, end, HOR::new(hor).unwrap()));
+        assert_eq!(
+            assign_arrays(&records.iter().collect::<Vec<_>>(), &arrays),
+            [Some(2), Some(1), None, Some(0), None, None]
+        );
+    }
+
+    #[test]
+    fn test_consensus() {
+        let seqs: [&[u8]; 4] = [b"ACGTACGT", b"ACGTTACGT", b"ACCTACGT", b"ACGTACGT"];
+        assert_eq!(consensus(&seqs), b"ACGTACGT");
+        let seqs: [&[u8]; 3] = [b"ACGT", b"AGT", b"AGT"];
+        assert_eq!(consensus(&seqs), b"AGT");
+        assert!(consensus(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_divergence_within_and_between_arrays() {
+        // Two arrays of the same HOR separated by a gap.
+        let fa = b">chr1\nAAAACCCCAAAACCCCAAAACCCCTTTTTTTTAAAACCGGAAAACCGGAAAACCGG\n";
+        let fai = b"chr1\t56\t6\t56\t57\n";
+        let mut fasta = IndexedFasta::from_reader(Cursor::new(fa), &fai[..]).unwrap();
+        let hor = HOR::new("S1C1/5/19H1L.1-2").unwrap();
+        let records = [0, 8, 16, 32, 40, 48]
+            .into_iter()
+            .map(|st| StvRecord::new("chr1", st, st + 8, hor.clone()))
+            .collect::<Vec<StvRecord>>();
+        let arrays = find_hor_arrays(
+            &records,
+            Some(crate::ArrayOpts {
+                max_gap: 4,
+                max_interruption: 4,
+            }),
+        );
+        assert_eq!(arrays.len(), 2);
+
+        let divs = hor_copy_divergence(
+            &records,
+            &arrays,
+            &mut fasta,
+            Some(DivergenceOpts { n_neighbors: 1 }),
+        )
+        .unwrap();
+        assert_eq!(divs.len(), 6);
+        assert!(divs.iter().all(|div| div.n_copies == 3));
+        assert_eq!(
+            divs.iter().map(|div| div.array_idx).collect::<Vec<usize>>(),
+            [0, 0, 0, 1, 1, 1]
+        );
+        // Homogenized within each array.
+        assert!(divs.iter().all(
+            |div| div.consensus_divergence == Some(0.0) && div.pairwise_divergence == Some(0.0)
+        ));
+        // CCCC vs CCGG
+        assert!(divs
+            .iter()
+            .all(|div| div.between_array_divergence == Some(25.0)));
+
+        let mut output = vec![];
+        write_to_divergence_bed(&mut output, &divs[..1]).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "chr1\t0\t8\tS1C1/5/19H1L.1-2\t3\t0.00\t0.00\t25.00\n"
+        );
+    }
+
+    #[test]
+    fn test_divergence_single_copy() {
+        let fa = b">chr1\nAAAACCCC\n";
+        let fai = b"chr1\t8\t6\t8\t9\n";
+        let mut fasta = IndexedFasta::from_reader(Cursor::new(fa), &fai[..]).unwrap();
+        let records = [StvRecord::new(
+            "chr1",
+            0,
+            8,
+            HOR::new("S1C1/5/19H1L.1-2").unwrap(),
+        )];
+        let arrays = find_hor_arrays(&records, None);
+        let divs = hor_copy_divergence(&records, &arrays, &mut fasta, None).unwrap();
+        assert_eq!(divs[0].n_copies, 1);
+        assert_eq!(divs[0].consensus_divergence, None);
+        assert_eq!(divs[0].pairwise_divergence, None);
+        assert_eq!(divs[0].between_array_divergence, None);
+    }
+}
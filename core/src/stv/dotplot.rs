@@ -0,0 +1,303 @@
+use std::{collections::HashMap, io::Write};
+
+use eyre::{bail, ContextCompat};
+use itertools::Itertools;
+
+use crate::{utils::edit_distance, Monomer};
+
+/// Viridis color stops from low to high identity.
+const COLOR_STOPS: [[u8; 3]; 5] = [
+    [68, 1, 84],
+    [59, 82, 139],
+    [33, 145, 140],
+    [94, 201, 98],
+    [253, 231, 37],
+];
+
+/// Get the color of an identity in `[0, 1]` by interpolating [`COLOR_STOPS`].
+fn identity_color(identity: f32) -> [u8; 3] {
+    let pos = identity.clamp(0.0, 1.0) * (COLOR_STOPS.len() - 1) as f32;
+    let idx = (pos.floor() as usize).min(COLOR_STOPS.len() - 2);
+    let frac = pos - idx as f32;
+    let (low, high) = (COLOR_STOPS[idx], COLOR_STOPS[idx + 1]);
+    [0, 1, 2].map(|i| (low[i] as f32 + frac * (high[i] as f32 - low[i] as f32)).round() as u8)
+}
+
+/// A monomer-label self-similarity matrix of an array.
+/// * The array is split into non-overlapping windows of monomers.
+/// * The identity between two windows is `1 - (edit distance / max window length)` of their monomer labels.
+/// * Monomer labels are compared by name, ignoring [`crate::Strand`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomers_from_bed, SelfSimilarity};
+///
+/// let monomers = read_monomers_from_bed("test/mons.bed", |_| false).unwrap();
+/// let selfsim = SelfSimilarity::new(&monomers["chm1_chr15:3977696-8919402"], 11).unwrap();
+/// assert_eq!(selfsim.n_windows(), 2);
+/// assert_eq!(selfsim.identity(0, 1), 1.0);
+///
+/// let mut svg = vec![];
+/// selfsim.write_svg(&mut svg, 10).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfSimilarity {
+    window: usize,
+    coords: Vec<(u64, u64)>,
+    identities: Vec<f32>,
+}
+
+impl SelfSimilarity {
+    /// Construct a [`SelfSimilarity`] matrix from [`Monomer`]s ordered by position.
+    ///
+    /// # Args
+    /// * `monomers`
+    ///     * [`Monomer`]s with their `(st, end, identity, monomer)`. See [`crate::read_monomers_from_bed`].
+    /// * `window`
+    ///     * Number of monomers per window.
+    pub fn new(monomers: &[(u64, u64, f32, Monomer)], window: usize) -> eyre::Result<Self> {
+        if window == 0 {
+            bail!("Window size must be greater than 0.")
+        }
+        // Convert monomers to integer labels for faster comparison.
+        let mut label_ids: HashMap<String, u32> = HashMap::new();
+        let labels = monomers
+            .iter()
+            .map(|(_, _, _, mon)| {
+                let n_labels = label_ids.len() as u32;
+                *label_ids.entry(mon.to_string()).or_insert(n_labels)
+            })
+            .collect_vec();
+
+        let windows = labels.chunks(window).collect_vec();
+        let coords = monomers
+            .chunks(window)
+            .map(|mons| {
+                let min_st = mons.iter().map(|m| m.0).min().unwrap_or_default();
+                let max_end = mons.iter().map(|m| m.1).max().unwrap_or_default();
+                (min_st, max_end)
+            })
+            .collect_vec();
+
+        // Arrays are highly repetitive so only compare unique windows.
+        let mut uniq_windows: HashMap<&[u32], usize> = HashMap::new();
+        let window_ids = windows
+            .iter()
+            .map(|win| {
+                let n_uniq = uniq_windows.len();
+                *uniq_windows.entry(win).or_insert(n_uniq)
+            })
+            .collect_vec();
+        let mut uniq_identities: HashMap<(usize, usize), f32> = HashMap::new();
+
+        let n = windows.len();
+        let mut identities = vec![0.0; n * n];
+        for i in 0..n {
+            for j in i..n {
+                let (id_i, id_j) = (window_ids[i], window_ids[j]);
+                let key = (id_i.min(id_j), id_i.max(id_j));
+                let identity = *uniq_identities.entry(key).or_insert_with(|| {
+                    let (win_i, win_j) = (windows[i], windows[j]);
+                    let max_len = win_i.len().max(win_j.len());
+                    let dst = edit_distance(win_i, win_j, |a, b| a == b);
+                    1.0 - (dst as f32 / max_len as f32)
+                });
+                identities[i * n + j] = identity;
+                identities[j * n + i] = identity;
+            }
+        }
+        Ok(Self {
+            window,
+            coords,
+            identities,
+        })
+    }
+
+    /// Number of monomers per window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Number of windows.
+    pub fn n_windows(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Get the `(st, end)` coordinates of each window.
+    pub fn window_coords(&self) -> &[(u64, u64)] {
+        &self.coords
+    }
+
+    /// Get the identity between windows `i` and `j`.
+    ///
+    /// # Panics
+    /// * If either `i` or `j` is out of bounds.
+    pub fn identity(&self, i: usize, j: usize) -> f32 {
+        let n = self.n_windows();
+        assert!(i < n && j < n, "Window index out of bounds.");
+        self.identities[i * n + j]
+    }
+
+    /// Write the matrix as an `SVG` heatmap.
+    ///
+    /// # Args
+    /// * `output`
+    ///     * Writer for `SVG`.
+    /// * `cell_size`
+    ///     * Size in pixels of each window.
+    pub fn write_svg(&self, mut output: impl Write, cell_size: u32) -> eyre::Result<()> {
+        let n = self.n_windows();
+        let size = image_size(n, cell_size)?;
+        let [r, g, b] = identity_color(0.0);
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}" shape-rendering="crispEdges">"#
+        )?;
+        writeln!(
+            output,
+            r#"<rect width="{size}" height="{size}" fill="rgb({r},{g},{b})"/>"#
+        )?;
+        for i in 0..n {
+            for j in 0..n {
+                let identity = self.identity(i, j);
+                // Already background.
+                if identity <= 0.0 {
+                    continue;
+                }
+                let [r, g, b] = identity_color(identity);
+                let ((row_st, row_end), (col_st, col_end)) = (self.coords[i], self.coords[j]);
+                writeln!(
+                    output,
+                    r#"<rect x="{}" y="{}" width="{cell_size}" height="{cell_size}" fill="rgb({r},{g},{b})"><title>{row_st}-{row_end} x {col_st}-{col_end}: {identity:.3}</title></rect>"#,
+                    j as u64 * u64::from(cell_size),
+                    i as u64 * u64::from(cell_size),
+                )?;
+            }
+        }
+        writeln!(output, "</svg>")?;
+        Ok(())
+    }
+
+    /// Write the matrix as a `PNG` heatmap.
+    ///
+    /// # Args
+    /// * `output`
+    ///     * Writer for `PNG`.
+    /// * `cell_size`
+    ///     * Size in pixels of each window.
+    pub fn write_png(&self, output: impl Write, cell_size: u32) -> eyre::Result<()> {
+        let n = self.n_windows();
+        let size = image_size(n, cell_size)?;
+        if size == 0 {
+            bail!("Cannot write empty matrix.")
+        }
+        let mut encoder = png::Encoder::new(output, size, size);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+
+        let cell_size = cell_size as usize;
+        let n_bytes = (size as usize)
+            .checked_mul(size as usize)
+            .and_then(|n_pixels| n_pixels.checked_mul(3))
+            .with_context(|| format!("Image of {size}x{size} pixels is too large."))?;
+        let mut pixels = Vec::with_capacity(n_bytes);
+        for i in 0..n {
+            let row = (0..n)
+                .flat_map(|j| identity_color(self.identity(i, j)).repeat(cell_size))
+                .collect_vec();
+            for _ in 0..cell_size {
+                pixels.extend(&row);
+            }
+        }
+        writer.write_image_data(&pixels)?;
+        Ok(())
+    }
+}
+
+/// Get the width in pixels of an image of `n` windows with `cell_size` pixels each.
+fn image_size(n: usize, cell_size: u32) -> eyre::Result<u32> {
+    let Some(size) = (n as u64)
+        .checked_mul(u64::from(cell_size))
+        .and_then(|size| u32::try_from(size).ok())
+    else {
+        bail!("Image of {n} windows with {cell_size} pixels each is too large.")
+    };
+    Ok(size)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{stv::test_utils::monomers, SelfSimilarity};
+
+    use super::image_size;
+
+    #[test]
+    fn test_selfsim() {
+        let mons = monomers(&[1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 4, 4, 5, 6]);
+        let selfsim = SelfSimilarity::new(&mons, 4).unwrap();
+
+        assert_eq!(selfsim.n_windows(), 4);
+        assert_eq!(
+            selfsim.window_coords(),
+            [(0, 684), (684, 1368), (1368, 2052), (2052, 2394)]
+        );
+        assert_eq!(selfsim.identity(0, 0), 1.0);
+        assert_eq!(selfsim.identity(0, 1), 1.0);
+        assert_eq!(selfsim.identity(0, 2), 0.75);
+        assert_eq!(selfsim.identity(2, 0), 0.75);
+        assert_eq!(selfsim.identity(0, 3), 0.0);
+    }
+
+    #[test]
+    fn test_selfsim_invalid_window() {
+        let mons = monomers(&[1, 2, 3]);
+        assert!(SelfSimilarity::new(&mons, 0).is_err());
+    }
+
+    #[test]
+    fn test_selfsim_svg() {
+        let mons = monomers(&[1, 2, 1, 2, 3, 4]);
+        let selfsim = SelfSimilarity::new(&mons, 2).unwrap();
+        let mut svg = vec![];
+        selfsim.write_svg(&mut svg, 10).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="30""#)
+        );
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // Background and 5 non-zero identity cells. (0,0), (0,1), (1,0), (1,1), (2,2)
+        assert_eq!(svg.matches("<rect").count(), 6);
+        // Tooltip has the row and column windows.
+        let cell = svg
+            .lines()
+            .find(|line| line.starts_with(r#"<rect x="0" y="10""#));
+        assert!(cell
+            .unwrap()
+            .ends_with("<title>342-684 x 0-342: 1.000</title></rect>"));
+    }
+
+    #[test]
+    fn test_selfsim_png() {
+        let mons = monomers(&[1, 2, 1, 2, 3, 4]);
+        let selfsim = SelfSimilarity::new(&mons, 2).unwrap();
+        let mut png_data = vec![];
+        selfsim.write_png(&mut png_data, 4).unwrap();
+
+        let decoder = png::Decoder::new(png_data.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (12, 12));
+    }
+
+    #[test]
+    fn test_selfsim_image_size() {
+        assert_eq!(image_size(40_000, 1).unwrap(), 40_000);
+        assert!(image_size(70_000, 70_000).is_err());
+        // Too large to write at a cell size that overflows.
+        let mons = monomers(&[1, 2, 1, 2, 3, 4]);
+        let selfsim = SelfSimilarity::new(&mons, 2).unwrap();
+        assert!(selfsim.write_png(std::io::sink(), u32::MAX).is_err());
+        assert!(selfsim.write_svg(std::io::sink(), u32::MAX).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use eyre::{bail, ContextCompat};
+use itertools::Itertools;
+
+use crate::{utils::reverse_complement, Strand};
+
+use super::{
+    io::{ContigMonomers, StvRecord},
+    region::Region,
+};
+
+/// Number of bases per line when writing `FASTA`.
+const LINE_WIDTH: usize = 80;
+
+/// A `.fai` record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FaiRecord {
+    length: u64,
+    offset: u64,
+    line_bases: u64,
+    line_width: u64,
+}
+
+/// A `FASTA` file with a `samtools faidx` index for random access.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use rs_asat_hor::IndexedFasta;
+///
+/// let fa = b">chr1\nACGTA\nCGTAC\nG\n";
+/// let fai = b"chr1\t11\t6\t5\t6\n";
+/// let mut fasta = IndexedFasta::from_reader(Cursor::new(fa), &fai[..]).unwrap();
+/// assert_eq!(fasta.fetch("chr1", 3, 8).unwrap(), b"TACGT");
+/// ```
+pub struct IndexedFasta<R> {
+    reader: R,
+    index: HashMap<String, FaiRecord>,
+}
+
+impl IndexedFasta<BufReader<File>> {
+    /// Open a `FASTA` file and its index, `{fasta}.fai`.
+    pub fn new(fasta: impl AsRef<Path>) -> eyre::Result<Self> {
+        let fasta = fasta.as_ref();
+        let mut fai = fasta.as_os_str().to_owned();
+        fai.push(".fai");
+        let fai = File::open(&fai)
+            .map_err(|err| eyre::eyre!("Cannot open index {fai:?} of {fasta:?}. {err}"))?;
+        IndexedFasta::from_reader(BufReader::new(File::open(fasta)?), BufReader::new(fai))
+    }
+}
+
+impl<R: Read + Seek> IndexedFasta<R> {
+    /// Construct an [`IndexedFasta`] from a `FASTA` reader and a `.fai` index reader.
+    pub fn from_reader(reader: R, fai: impl BufRead) -> eyre::Result<Self> {
+        let mut index = HashMap::new();
+        for (i, line) in fai.lines().enumerate() {
+            let line = line?;
+            let Some((name, length, offset, line_bases, line_width)) =
+                line.trim().split('\t').take(5).collect_tuple()
+            else {
+                bail!("Invalid fai record at line {}, {line}.", i + 1)
+            };
+            let record = FaiRecord {
+                length: length.parse()?,
+                offset: offset.parse()?,
+                line_bases: line_bases.parse()?,
+                line_width: line_width.parse()?,
+            };
+            if record.line_bases == 0 || record.line_width < record.line_bases {
+                bail!(
+                    "Invalid fai record at line {}, {line}. Line bases must be non-zero and at most line width.",
+                    i + 1
+                )
+            }
+            index.insert(name.to_owned(), record);
+        }
+        Ok(Self { reader, index })
+    }
+
+    /// Check if a sequence is in the index.
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    /// Fetch the sequence of a `0`-based, half-open interval.
+    pub fn fetch(&mut self, name: &str, st: u64, end: u64) -> eyre::Result<Vec<u8>> {
+        let rec = self
+            .index
+            .get(name)
+            .with_context(|| format!("Sequence {name} not in index."))?;
+        if st > end || end > rec.length {
+            bail!(
+                "Invalid interval {name}:{st}-{end} of sequence with length {}.",
+                rec.length
+            )
+        }
+        if st == end {
+            return Ok(vec![]);
+        }
+        let fn_byte_pos =
+            |pos: u64| rec.offset + (pos / rec.line_bases) * rec.line_width + pos % rec.line_bases;
+        let (byte_st, byte_end) = (fn_byte_pos(st), fn_byte_pos(end - 1) + 1);
+
+        let mut buf = vec![0; (byte_end - byte_st) as usize];
+        self.reader.seek(SeekFrom::Start(byte_st))?;
+        self.reader.read_exact(&mut buf)?;
+        buf.retain(|b| !matches!(b, b'\n' | b'\r'));
+        Ok(buf)
+    }
+
+    /// Fetch an interval, falling back to the parent contig if `name` is a [`crate::Region`] not in the index.
+    fn fetch_record(&mut self, name: &str, st: u64, end: u64) -> eyre::Result<Vec<u8>> {
+        if self.contains(name) {
+            return self.fetch(name, st, end);
+        }
+        let Ok(region) = Region::from_str(name) else {
+            bail!("Sequence {name} not in index.")
+        };
+        self.fetch(&region.name, region.to_parent(st), region.to_parent(end))
+    }
+
+    /// Fetch the sequence of a [`StvRecord`] in the orientation of its HOR.
+    /// * Records on the [`Strand::Minus`] are reverse complemented.
+    pub(crate) fn fetch_stv(&mut self, rec: &StvRecord) -> eyre::Result<Vec<u8>> {
+        let seq = self.fetch_record(&rec.chrom, rec.st, rec.end)?;
+        Ok(match rec.strand() {
+            Some(Strand::Minus) => reverse_complement(&seq),
+            Some(Strand::Plus) | None => seq,
+        })
+    }
+}
+
+/// Write a `FASTA` record, wrapping sequence lines.
+pub(crate) fn write_fasta_record(
+    output: &mut impl Write,
+    header: &str,
+    seq: &[u8],
+) -> eyre::Result<()> {
+    writeln!(output, ">{header}")?;
+    for line in seq.chunks(LINE_WIDTH) {
+        output.write_all(line)?;
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+/// Extract the sequence of each [`StvRecord`] and write them as a multi-`FASTA`.
+/// * Headers are `{stv}::{chrom}:{st}-{end}({strand})`.
+/// * Records on the [`Strand::Minus`] are reverse complemented.
+/// * Records named as a [`crate::Region`] are fetched from the parent contig if the region is not in the index.
+///
+/// # Args
+/// * `output`
+///     * Writer for `FASTA` records.
+/// * `fasta`
+///     * [`IndexedFasta`] of the assembly.
+/// * `records`
+///     * [`StvRecord`]s to extract.
+pub fn write_stv_fasta<'a, R: Read + Seek>(
+    mut output: impl Write,
+    fasta: &mut IndexedFasta<R>,
+    records: impl IntoIterator<Item = &'a StvRecord>,
+) -> eyre::Result<()> {
+    for rec in records {
+        let strand = rec.strand().unwrap_or(Strand::Plus);
+        let seq = fasta.fetch_stv(rec)?;
+        let header = format!(
+            "{}::{}:{}-{}({strand})",
+            rec.hor, rec.chrom, rec.st, rec.end
+        );
+        write_fasta_record(&mut output, &header, &seq)?;
+    }
+    Ok(())
+}
+
+/// Extract the sequence of each monomer and write them as a multi-`FASTA`.
+/// * Headers are `{monomer}::{chrom}:{st}-{end}({strand})`.
+/// * Monomers are written by contig and in order.
+/// * See [`write_stv_fasta`].
+pub fn write_monomer_fasta<R: Read + Seek>(
+    mut output: impl Write,
+    fasta: &mut IndexedFasta<R>,
+    monomers: &ContigMonomers,
+) -> eyre::Result<()> {
+    for (chrom, mons) in monomers.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+        for (st, end, _, mon) in mons {
+            let strand = mon.strand.unwrap_or(Strand::Plus);
+            let mut seq = fasta.fetch_record(chrom, *st, *end)?;
+            if strand == Strand::Minus {
+                seq = reverse_complement(&seq);
+            }
+            let header = format!("{mon}::{chrom}:{st}-{end}({strand})");
+            write_fasta_record(&mut output, &header, &seq)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, io::Cursor};
+
+    use crate::{
+        write_monomer_fasta, write_stv_fasta, IndexedFasta, Monomer, Strand, StvRecord, HOR,
+    };
+
+    const FASTA: &[u8] = b">chr1\nAACCG\nGTTAC\nGT\n>chr2\nACGT\n";
+    const FAI: &[u8] = b"chr1\t12\t6\t5\t6\nchr2\t4\t27\t4\t5\n";
+
+    fn fasta() -> IndexedFasta<Cursor<&'static [u8]>> {
+        IndexedFasta::from_reader(Cursor::new(FASTA), FAI).unwrap()
+    }
+
+    #[test]
+    fn test_fetch() {
+        let mut fasta = fasta();
+        assert_eq!(fasta.fetch("chr1", 0, 12).unwrap(), b"AACCGGTTACGT");
+        assert_eq!(fasta.fetch("chr1", 4, 6).unwrap(), b"GG");
+        assert_eq!(fasta.fetch("chr2", 1, 3).unwrap(), b"CG");
+        assert!(fasta.fetch("chr1", 4, 13).is_err());
+        assert!(fasta.fetch("chr3", 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_fai() {
+        for fai in ["chr1\t12\t6\t0\t1\n", "chr1\t12\t6\t8\t7\n"] {
+            assert!(IndexedFasta::from_reader(Cursor::new(FASTA), fai.as_bytes()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_write_stv_fasta() {
+        let mut fasta = fasta();
+        let hor = HOR::new("S1C1/5/19H1L.1-2").unwrap();
+        let rev_hor = HOR::from_monomers(&[
+            Monomer::new("S1C1/5/19H1L.2")
+                .unwrap()
+                .with_strand(Strand::Minus),
+            Monomer::new("S1C1/5/19H1L.1")
+                .unwrap()
+                .with_strand(Strand::Minus),
+        ])
+        .unwrap()
+        .remove(0);
+        let records = [
+            StvRecord::new("chr1", 0, 4, hor),
+            // Region not in index.
+            StvRecord::new("chr1:2-12", 0, 4, rev_hor),
+        ];
+        let mut output = vec![];
+        write_stv_fasta(&mut output, &mut fasta, &records).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">S1C1/5/19H1L.1-2::chr1:0-4(+)\nAACC\n>S1C1/5/19H1L.2-1::chr1:2-12:0-4(-)\nCCGG\n"
+        );
+    }
+
+    #[test]
+    fn test_write_monomer_fasta() {
+        let mut fasta = fasta();
+        let monomers = HashMap::from_iter([(
+            String::from("chr2"),
+            vec![
+                (0, 2, 100.0, Monomer::new("S1C1/5/19H1L.1").unwrap()),
+                (
+                    2,
+                    4,
+                    100.0,
+                    Monomer::new("S1C1/5/19H1L.2")
+                        .unwrap()
+                        .with_strand(Strand::Minus),
+                ),
+            ],
+        )]);
+        let mut output = vec![];
+        write_monomer_fasta(&mut output, &mut fasta, &monomers).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">S1C1/5/19H1L.1::chr2:0-2(+)\nAC\n>S1C1/5/19H1L.2::chr2:2-4(-)\nAC\n"
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::{collections::HashMap, ops::Range};
+
+use eyre::bail;
+use itertools::Itertools;
+use suffix::SuffixTable;
+
+use crate::{Monomer, HOR};
+
+use super::{
+    array::HORArray,
+    io::{monomers_to_stv_records, ContigMonomers, StvRecord},
+};
+
+/// Delimits contigs in the index text. Never assigned to a monomer label.
+const SEPARATOR: char = '\u{800}';
+/// Number of bytes of each character in the index text.
+/// * All monomer labels are encoded in the 3-byte range `U+0801..=U+FFFF` so byte positions map directly to monomers.
+const CHAR_LEN: usize = 3;
+
+/// Get the character of the `idx`-th monomer label, skipping surrogates.
+fn label_char(idx: usize) -> Option<char> {
+    let mut code = 0x801 + idx as u32;
+    if code >= 0xD800 {
+        code += 0x800;
+    }
+    (code <= 0xFFFF).then(|| char::from_u32(code)).flatten()
+}
+
+/// Compute the longest common prefix of each suffix and the previous suffix in `table` with Kasai's algorithm.
+fn lcp_array(text: &[u8], table: &[u32]) -> Vec<usize> {
+    let mut rank = vec![0; table.len()];
+    for (i, pos) in table.iter().enumerate() {
+        rank[*pos as usize] = i;
+    }
+    let mut lcp = vec![0; table.len()];
+    let mut len = 0;
+    for pos in 0..text.len() {
+        if rank[pos] == 0 {
+            len = 0;
+            continue;
+        }
+        let prev = table[rank[pos] - 1] as usize;
+        while pos + len < text.len()
+            && prev + len < text.len()
+            && text[pos + len] == text[prev + len]
+        {
+            len += 1;
+        }
+        lcp[rank[pos]] = len;
+        len = len.saturating_sub(1);
+    }
+    lcp
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IndexedMonomer {
+    st: u64,
+    end: u64,
+    monomer: Monomer,
+    /// Index of StV in [`StvIndex::records`].
+    record: usize,
+    /// Index of monomer within StV.
+    idx: usize,
+}
+
+/// A match of a monomer subsequence in a [`StvIndex`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StvHit<'a> {
+    /// StV containing the match.
+    pub stv: &'a StvRecord,
+    /// Start of the first matched monomer.
+    pub st: u64,
+    /// End of the last matched monomer.
+    pub end: u64,
+    /// Index of the first matched monomer within the StV.
+    pub monomer_idx: usize,
+}
+
+/// The longest monomer substring shared by two arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedMonomers {
+    pub monomers: Vec<Monomer>,
+    /// `(st, end)` coordinates in the first array.
+    pub coords_1: (u64, u64),
+    /// `(st, end)` coordinates in the second array.
+    pub coords_2: (u64, u64),
+}
+
+/// A suffix-array index over the monomer sequences of StVs.
+/// * Each unique monomer label is encoded as a character and contigs are concatenated into a single text.
+/// * Combine samples into a single [`ContigMonomers`] with unique contig names to index a population.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomers_from_bed, StvIndex, HOR};
+///
+/// let monomers = read_monomers_from_bed("test/mons.bed", |_| false).unwrap();
+/// let index = StvIndex::new(&monomers).unwrap();
+///
+/// let hits = index.find(&HOR::new("S2C15H1L.5-3").unwrap());
+/// assert_eq!(hits.len(), 2);
+/// assert_eq!((hits[0].st, hits[0].end, hits[0].monomer_idx), (2733086, 2733598, 6));
+/// ```
+pub struct StvIndex {
+    table: SuffixTable<'static, 'static>,
+    /// Longest common prefix in bytes of each suffix in `table` and the previous suffix.
+    lcp: Vec<usize>,
+    labels: HashMap<String, char>,
+    /// Monomer of each character in the index text. [`None`] if [`SEPARATOR`].
+    monomers: Vec<Option<IndexedMonomer>>,
+    records: Vec<StvRecord>,
+    /// Range of each contig's monomers in `monomers`.
+    contigs: HashMap<String, Range<usize>>,
+}
+
+impl StvIndex {
+    /// Construct a [`StvIndex`] from monomers.
+    ///
+    /// # Args
+    /// * `monomers`
+    ///     * [`ContigMonomers`] from [`crate::read_monomers_from_bed`]. Converted to StVs with [`crate::monomers_to_hor`].
+    ///
+    /// # Returns
+    /// * [`StvIndex`] or an error if there are too many unique monomer labels to encode.
+    pub fn new(monomers: &ContigMonomers) -> eyre::Result<Self> {
+        let mut labels: HashMap<String, char> = HashMap::new();
+        let mut text = String::new();
+        let mut indexed = vec![];
+        let mut records = vec![];
+        let mut contigs = HashMap::new();
+
+        for (chrom, mons) in monomers.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+            let contig_st = indexed.len();
+            let chrom_records = monomers_to_stv_records(chrom, mons)?;
+            let mut mons_iter = mons.iter();
+            for rec in chrom_records {
+                for idx in 0..rec.hor.n_monomers() {
+                    let Some((st, end, _, mon)) = mons_iter.next() else {
+                        break;
+                    };
+                    let n_labels = labels.len();
+                    let label = match labels.get(&mon.to_string()) {
+                        Some(label) => *label,
+                        None => {
+                            let Some(label) = label_char(n_labels) else {
+                                bail!("Too many unique monomers ({n_labels}) to index.")
+                            };
+                            labels.insert(mon.to_string(), label);
+                            label
+                        }
+                    };
+                    text.push(label);
+                    indexed.push(Some(IndexedMonomer {
+                        st: *st,
+                        end: *end,
+                        monomer: mon.clone(),
+                        record: records.len(),
+                        idx,
+                    }));
+                }
+                records.push(rec);
+            }
+            contigs.insert(chrom.clone(), contig_st..indexed.len());
+            text.push(SEPARATOR);
+            indexed.push(None);
+        }
+        let table = SuffixTable::new(text);
+        let lcp = lcp_array(table.text().as_bytes(), table.table());
+        Ok(Self {
+            table,
+            lcp,
+            labels,
+            monomers: indexed,
+            records,
+            contigs,
+        })
+    }
+
+    /// Get all indexed StVs.
+    pub fn records(&self) -> &[StvRecord] {
+        &self.records
+    }
+
+    /// Encode monomers as index text. [`None`] if any monomer is not in the index.
+    fn encode<'a>(&self, monomers: impl IntoIterator<Item = &'a Monomer>) -> Option<String> {
+        monomers
+            .into_iter()
+            .map(|mon| self.labels.get(&mon.to_string()))
+            .collect()
+    }
+
+    /// Find every StV containing a monomer subsequence.
+    /// * Monomers are compared by label, ignoring [`crate::Strand`].
+    ///
+    /// # Args
+    /// * `query`
+    ///     * Monomer subsequence as a [`HOR`].
+    ///
+    /// # Returns
+    /// * [`StvHit`]s sorted by position in the index.
+    pub fn find(&self, query: &HOR) -> Vec<StvHit<'_>> {
+        let Some(query) = self.encode(query.monomers()).filter(|q| !q.is_empty()) else {
+            return vec![];
+        };
+        let n_query = query.chars().count();
+        self.table
+            .positions(&query)
+            .iter()
+            .map(|pos| *pos as usize / CHAR_LEN)
+            .sorted()
+            .filter_map(|idx| {
+                let first = self.monomers[idx].as_ref()?;
+                let last = self.monomers[idx + n_query - 1].as_ref()?;
+                // StVs of a contig are not delimited so skip matches across them.
+                if first.record != last.record {
+                    return None;
+                }
+                Some(StvHit {
+                    stv: &self.records[first.record],
+                    st: first.st,
+                    end: last.end,
+                    monomer_idx: first.idx,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the range of indexed monomers within an array's coordinates.
+    /// * Monomers of each contig are expected to be sorted by position.
+    fn array_range(&self, array: &HORArray) -> Range<usize> {
+        let Some(contig) = self.contigs.get(&array.chrom) else {
+            return 0..0;
+        };
+        let mons = &self.monomers[contig.clone()];
+        let st = mons.partition_point(|mon| mon.as_ref().is_some_and(|mon| mon.st < array.st));
+        let end = mons.partition_point(|mon| mon.as_ref().is_some_and(|mon| mon.end <= array.end));
+        contig.start + st..contig.start + end.max(st)
+    }
+
+    /// Find the longest monomer substring shared by two arrays.
+    /// * Substrings can span multiple StVs within an array.
+    /// * Reuses the index's suffix table and LCP array in a single pass, so each call is `O(n)` in the number of indexed monomers.
+    ///
+    /// # Args
+    /// * `array_1`
+    ///     * First [`HORArray`]. Indexed monomers within its coordinates are used.
+    /// * `array_2`
+    ///     * Second [`HORArray`].
+    ///
+    /// # Returns
+    /// * [`SharedMonomers`] or [`None`] if no monomers are shared.
+    pub fn longest_shared(&self, array_1: &HORArray, array_2: &HORArray) -> Option<SharedMonomers> {
+        let (range_1, range_2) = (self.array_range(array_1), self.array_range(array_2));
+        let fn_update_best = |best: &mut Option<(usize, usize, usize)>, idx_1, idx_2, len| {
+            if len > best.map_or(0, |b: (usize, usize, usize)| b.2) {
+                *best = Some((idx_1, idx_2, len));
+            }
+        };
+
+        // For each array, keep the previous suffix in it with the longest match to the current suffix.
+        // A match is the minimum LCP between both suffixes, truncated at the end of either array.
+        let mut best: Option<(usize, usize, usize)> = None;
+        let mut prev_1: Option<(usize, usize)> = None;
+        let mut prev_2: Option<(usize, usize)> = None;
+        for (pos, lcp) in self.table.table().iter().zip(&self.lcp) {
+            let len = lcp / CHAR_LEN;
+            prev_1 = prev_1.map(|(idx, prev_len)| (idx, prev_len.min(len)));
+            prev_2 = prev_2.map(|(idx, prev_len)| (idx, prev_len.min(len)));
+            let pos = *pos as usize;
+            if !pos.is_multiple_of(CHAR_LEN) {
+                continue;
+            }
+            let idx = pos / CHAR_LEN;
+            let (in_1, in_2) = (range_1.contains(&idx), range_2.contains(&idx));
+            if let (true, Some((idx_2, len))) = (in_1, prev_2) {
+                fn_update_best(&mut best, idx, idx_2, len.min(range_1.end - idx));
+            }
+            if let (true, Some((idx_1, len))) = (in_2, prev_1) {
+                fn_update_best(&mut best, idx_1, idx, len.min(range_2.end - idx));
+            }
+            if in_1 && prev_1.is_none_or(|(_, len)| range_1.end - idx > len) {
+                prev_1 = Some((idx, range_1.end - idx));
+            }
+            if in_2 && prev_2.is_none_or(|(_, len)| range_2.end - idx > len) {
+                prev_2 = Some((idx, range_2.end - idx));
+            }
+        }
+
+        let (idx_1, idx_2, len) = best?;
+        let shared_1 = &self.monomers[idx_1..idx_1 + len];
+        let shared_2 = &self.monomers[idx_2..idx_2 + len];
+        let fn_coords = |shared: &[Option<IndexedMonomer>]| {
+            let (first, last) = (shared.first()?.as_ref()?, shared.last()?.as_ref()?);
+            Some((first.st, last.end))
+        };
+        Some(SharedMonomers {
+            monomers: shared_1
+                .iter()
+                .flatten()
+                .map(|mon| mon.monomer.clone())
+                .collect(),
+            coords_1: fn_coords(shared_1)?,
+            coords_2: fn_coords(shared_2)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::{find_hor_arrays, stv::test_utils::contig_monomers, StvIndex, HOR};
+
+    #[test]
+    fn test_index_find() {
+        let mons = contig_monomers(&[
+            ("hap1", &["1", "2", "3", "4", "1", "2", "4"]),
+            ("hap2", &["1", "2", "3", "4", "5", "6/4", "5"]),
+        ]);
+        let index = StvIndex::new(&mons).unwrap();
+        assert_eq!(index.records().len(), 4);
+
+        let hits = index.find(&HOR::new("S1C1/5/19H1L.2-3").unwrap());
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.stv.chrom.as_str(), hit.st, hit.end, hit.monomer_idx))
+                .collect_vec(),
+            [("hap1", 171, 513, 1), ("hap2", 171, 513, 1)]
+        );
+
+        let hits = index.find(&HOR::new("S1C1/5/19H1L.6/4_5").unwrap());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].stv.hor.to_string(), "S1C1/5/19H1L.1-5_6/4_5");
+
+        // Across StVs.
+        assert!(index
+            .find(&HOR::new("S1C1/5/19H1L.4_1").unwrap())
+            .is_empty());
+        // Not in index.
+        assert!(index.find(&HOR::new("S1C1/5/19H1L.7").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_index_longest_shared() {
+        let mons = contig_monomers(&[
+            ("hap1", &["1", "2", "3", "4", "5", "1", "2", "3", "4", "5"]),
+            ("hap2", &["1", "2", "3", "4", "5", "1", "2", "3", "5"]),
+        ]);
+        let index = StvIndex::new(&mons).unwrap();
+        let arrays = find_hor_arrays(index.records(), None);
+        assert_eq!(arrays.len(), 2);
+
+        let shared = index.longest_shared(&arrays[0], &arrays[1]).unwrap();
+        assert_eq!(
+            shared.monomers.iter().map(|mon| mon.to_string()).join(","),
+            "S1C1/5/19H1L.1,S1C1/5/19H1L.2,S1C1/5/19H1L.3,S1C1/5/19H1L.4,S1C1/5/19H1L.5,S1C1/5/19H1L.1,S1C1/5/19H1L.2,S1C1/5/19H1L.3"
+        );
+        assert_eq!(shared.coords_1, (0, 1368));
+        assert_eq!(shared.coords_2, (0, 1368));
+    }
+
+    #[test]
+    fn test_index_longest_shared_truncated() {
+        let hap_1 = ["1", "2", "3", "1", "2", "3", "4", "1", "2", "3", "4", "5"];
+        let hap_2 = ["3", "4", "1", "2", "3", "4", "5", "1", "2"];
+        let mons = contig_monomers(&[("hap1", &hap_1), ("hap2", &hap_2)]);
+        let index = StvIndex::new(&mons).unwrap();
+        let arrays = find_hor_arrays(index.records(), None);
+
+        // Compare against every window of each array.
+        for n_1 in 1..=hap_1.len() {
+            let mut array_1 = arrays[0].clone();
+            array_1.end = n_1 as u64 * 171;
+            let expected_len = (1..=n_1)
+                .rev()
+                .find(|len| {
+                    hap_1[..n_1]
+                        .windows(*len)
+                        .any(|w| hap_2.windows(*len).any(|w2| w == w2))
+                })
+                .unwrap();
+            let shared = index.longest_shared(&array_1, &arrays[1]).unwrap();
+            assert_eq!(shared.monomers.len(), expected_len, "{n_1}");
+            assert!(shared.coords_1.1 <= array_1.end);
+            assert_eq!(
+                shared.coords_1.1 - shared.coords_1.0,
+                shared.coords_2.1 - shared.coords_2.0
+            );
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use std::io::Write;
+
+use itertools::Itertools;
+
+use crate::{Monomer, Strand, HOR};
+
+use super::{
+    array::{find_hor_arrays, ArrayOpts},
+    io::{monomers_to_stv_records, ContigMonomers, StvRecord},
+};
+
+/// Options for [`find_inversions`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InversionOpts {
+    /// Minimum number of monomers in a run of the same strand.
+    /// * Shorter runs are merged into the preceding run.
+    pub min_monomers: usize,
+    /// Minimum monomer identity.
+    /// * Monomers with lower identity are ignored when calling runs but are kept in the reported StVs.
+    pub min_identity: f32,
+    /// [`ArrayOpts`] to find the arrays to search for inversions within.
+    pub array_opts: ArrayOpts,
+}
+
+impl Default for InversionOpts {
+    fn default() -> Self {
+        Self {
+            min_monomers: 4,
+            min_identity: 90.0,
+            array_opts: ArrayOpts::default(),
+        }
+    }
+}
+
+/// A run of monomers with the opposite strand of the array containing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inversion {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    /// Strand of inverted monomers.
+    pub strand: Strand,
+    /// Number of monomers in the inversion, including those below [`InversionOpts::min_identity`].
+    pub n_monomers: usize,
+    /// Mean identity of monomers in the inversion.
+    pub identity: f32,
+    /// StVs within the inversion.
+    pub stvs: Vec<HOR>,
+    /// StV directly before the inversion.
+    pub left_flank: StvRecord,
+    /// StV directly after the inversion.
+    pub right_flank: StvRecord,
+}
+
+/// Split monomers into runs of the same strand, merging runs shorter than `min_monomers` into the preceding run.
+fn strand_runs(
+    mons: &[&(u64, u64, f32, Monomer)],
+    min_monomers: usize,
+) -> Vec<(Strand, std::ops::Range<usize>)> {
+    let runs = mons
+        .iter()
+        .enumerate()
+        .chunk_by(|(_, (_, _, _, mon))| mon.strand.unwrap_or(Strand::Plus))
+        .into_iter()
+        .map(|(strand, run)| {
+            let idxs = run.map(|(i, _)| i).collect_vec();
+            (strand, idxs[0]..idxs[idxs.len() - 1] + 1)
+        })
+        .collect_vec();
+
+    let mut merged_runs: Vec<(Strand, std::ops::Range<usize>)> = vec![];
+    for (strand, run) in runs {
+        let Some((prev_strand, prev_run)) = merged_runs.last_mut() else {
+            merged_runs.push((strand, run));
+            continue;
+        };
+        // Extend if short or same strand after merging.
+        if run.len() < min_monomers || *prev_strand == strand {
+            prev_run.end = run.end;
+        } else if prev_run.len() < min_monomers {
+            // Short first run.
+            *prev_strand = strand;
+            prev_run.end = run.end;
+        } else {
+            merged_runs.push((strand, run));
+        }
+    }
+    merged_runs
+}
+
+/// Find inversions within arrays, runs of monomers on the opposite strand of their surrounding monomers.
+/// * Arrays are found with [`crate::find_hor_arrays`] and searched separately, each with the strand of most of its monomers.
+/// * Runs at the ends of an array are not inversions.
+///
+/// # Args
+/// * `monomers`
+///     * [`ContigMonomers`] from [`crate::read_monomers_from_bed`].
+/// * `opts`
+///     * [`InversionOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`Inversion`]s sorted by contig and position.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{find_inversions, read_monomers_from_bed};
+///
+/// let monomers = read_monomers_from_bed("test/mons.bed", |_| false).unwrap();
+/// let inversions = find_inversions(&monomers, None).unwrap();
+/// assert!(inversions.is_empty());
+/// ```
+pub fn find_inversions(
+    monomers: &ContigMonomers,
+    opts: Option<InversionOpts>,
+) -> eyre::Result<Vec<Inversion>> {
+    let opts = opts.unwrap_or_default();
+    let mut inversions = vec![];
+
+    for (chrom, mons) in monomers.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+        let mons = mons
+            .iter()
+            .filter(|(_, _, _, mon)| mon.strand.is_some())
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .cloned()
+            .collect_vec();
+        // StVs are built per strand so arrays span inversions.
+        let records = mons
+            .chunk_by(|a, b| a.3.strand == b.3.strand)
+            .map(|strand_mons| monomers_to_stv_records(chrom, strand_mons))
+            .flatten_ok()
+            .collect::<eyre::Result<Vec<StvRecord>>>()?;
+
+        for array in find_hor_arrays(&records, Some(opts.array_opts)) {
+            let array_mons = mons
+                .iter()
+                .filter(|(st, end, _, _)| *st >= array.st && *end <= array.end)
+                .collect_vec();
+            // Only call runs with monomers above the identity threshold.
+            let called_mons = array_mons
+                .iter()
+                .filter(|(_, _, identity, _)| *identity >= opts.min_identity)
+                .copied()
+                .collect_vec();
+            let runs = strand_runs(&called_mons, opts.min_monomers);
+
+            // Strand of array is strand with most monomers.
+            let (n_plus, n_minus) = runs
+                .iter()
+                .fold((0, 0), |(n_plus, n_minus), (strand, run)| match strand {
+                    Strand::Plus => (n_plus + run.len(), n_minus),
+                    Strand::Minus => (n_plus, n_minus + run.len()),
+                });
+            let array_strand = if n_plus >= n_minus {
+                Strand::Plus
+            } else {
+                Strand::Minus
+            };
+            // All monomers from the first to the last called monomer of a run.
+            let fn_run_monomers = |run: &std::ops::Range<usize>| {
+                let (st, end) = (called_mons[run.start].0, called_mons[run.end - 1].1);
+                array_mons
+                    .iter()
+                    .filter(|(mon_st, mon_end, _, _)| *mon_st >= st && *mon_end <= end)
+                    .cloned()
+                    .cloned()
+                    .collect_vec()
+            };
+
+            for ((_, left_run), (strand, run), (_, right_run)) in runs.iter().tuple_windows() {
+                if *strand == array_strand {
+                    continue;
+                }
+                let inv_mons = fn_run_monomers(run);
+                let stvs = monomers_to_stv_records(chrom, &inv_mons)?;
+                let (Some(left_flank), Some(right_flank)) = (
+                    monomers_to_stv_records(chrom, &fn_run_monomers(left_run))?.pop(),
+                    monomers_to_stv_records(chrom, &fn_run_monomers(right_run))?
+                        .into_iter()
+                        .next(),
+                ) else {
+                    continue;
+                };
+                let (Some(st), Some(end)) = (
+                    inv_mons.iter().map(|m| m.0).min(),
+                    inv_mons.iter().map(|m| m.1).max(),
+                ) else {
+                    continue;
+                };
+                let identity = inv_mons.iter().map(|m| m.2).sum::<f32>() / inv_mons.len() as f32;
+
+                inversions.push(Inversion {
+                    chrom: chrom.to_owned(),
+                    st,
+                    end,
+                    strand: *strand,
+                    n_monomers: inv_mons.len(),
+                    identity,
+                    stvs: stvs.into_iter().map(|rec| rec.hor).collect(),
+                    left_flank,
+                    right_flank,
+                });
+            }
+        }
+    }
+    Ok(inversions)
+}
+
+/// Write [`Inversion`]s as a `BED6+2` file.
+/// * Columns are `chrom`, `st`, `end`, inverted StVs delimited by `,`, mean identity, strand, left and right flanking StVs.
+///
+/// # Args
+/// * `output`
+///     * Writer for `BED` records.
+/// * `inversions`
+///     * [`Inversion`]s to write.
+pub fn write_to_inversion_bed<'a>(
+    mut output: impl Write,
+    inversions: impl IntoIterator<Item = &'a Inversion>,
+) -> eyre::Result<()> {
+    for inv in inversions {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{:.2}\t{}\t{}\t{}",
+            inv.chrom,
+            inv.st,
+            inv.end,
+            inv.stvs.iter().join(","),
+            inv.identity,
+            inv.strand,
+            inv.left_flank.hor,
+            inv.right_flank.hor,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        find_inversions, stv::test_utils, write_to_inversion_bed, ContigMonomers, InversionOpts,
+        Strand,
+    };
+
+    /// Tile monomers on `chr1` with a strand and identity each.
+    fn monomers(mons: &[(u8, Strand, f32)]) -> ContigMonomers {
+        let nums = mons.iter().map(|(num, _, _)| num).collect::<Vec<_>>();
+        let mons = test_utils::monomers(&nums)
+            .into_iter()
+            .zip(mons)
+            .map(|((st, end, _, mon), (_, strand, identity))| {
+                (st, end, *identity, mon.with_strand(*strand))
+            })
+            .collect();
+        ContigMonomers::from_iter([(String::from("chr1"), mons)])
+    }
+
+    #[test]
+    fn test_inversion() {
+        use Strand::{Minus, Plus};
+        let mons = monomers(&[
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+            (4, Minus, 100.0),
+            (3, Minus, 100.0),
+            (2, Minus, 100.0),
+            (1, Minus, 100.0),
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+        ]);
+        let inversions = find_inversions(&mons, None).unwrap();
+        assert_eq!(inversions.len(), 1);
+
+        let inv = &inversions[0];
+        assert_eq!(
+            (inv.st, inv.end, inv.strand, inv.n_monomers),
+            (684, 1368, Minus, 4)
+        );
+        assert_eq!(
+            inv.stvs
+                .iter()
+                .map(|stv| stv.to_string())
+                .collect::<Vec<_>>(),
+            ["S1C1/5/19H1L.4-1"]
+        );
+        assert_eq!(
+            (
+                inv.left_flank.st,
+                inv.left_flank.end,
+                inv.left_flank.hor.to_string()
+            ),
+            (0, 684, String::from("S1C1/5/19H1L.1-4"))
+        );
+        assert_eq!(
+            (
+                inv.right_flank.st,
+                inv.right_flank.end,
+                inv.right_flank.hor.to_string()
+            ),
+            (1368, 2052, String::from("S1C1/5/19H1L.1-4"))
+        );
+
+        let mut output = vec![];
+        write_to_inversion_bed(&mut output, &inversions).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "chr1\t684\t1368\tS1C1/5/19H1L.4-1\t100.00\t-\tS1C1/5/19H1L.1-4\tS1C1/5/19H1L.1-4\n"
+        );
+    }
+
+    #[test]
+    fn test_inversion_thresholds() {
+        use Strand::{Minus, Plus};
+        let mons = monomers(&[
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+            (4, Minus, 80.0),
+            (3, Minus, 100.0),
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+        ]);
+        // Too short.
+        assert!(find_inversions(&mons, None).unwrap().is_empty());
+
+        let opts = InversionOpts {
+            min_monomers: 1,
+            min_identity: 0.0,
+            ..Default::default()
+        };
+        let inversions = find_inversions(&mons, Some(opts)).unwrap();
+        assert_eq!(inversions.len(), 1);
+        assert_eq!(
+            (inversions[0].n_monomers, inversions[0].identity),
+            (2, 90.0)
+        );
+
+        // Low identity monomer at the edge of the run isn't called.
+        let opts = InversionOpts {
+            min_monomers: 1,
+            min_identity: 90.0,
+            ..Default::default()
+        };
+        let inversions = find_inversions(&mons, Some(opts)).unwrap();
+        assert_eq!(inversions[0].n_monomers, 1);
+    }
+
+    #[test]
+    fn test_inversion_keeps_low_identity_monomers() {
+        use Strand::{Minus, Plus};
+        let mons = monomers(&[
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+            (4, Minus, 100.0),
+            (3, Minus, 80.0),
+            (2, Minus, 100.0),
+            (1, Minus, 100.0),
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+        ]);
+        let opts = InversionOpts {
+            min_monomers: 3,
+            ..Default::default()
+        };
+        let inversions = find_inversions(&mons, Some(opts)).unwrap();
+        assert_eq!(inversions.len(), 1);
+        let inv = &inversions[0];
+        assert_eq!(
+            (inv.st, inv.end, inv.n_monomers, inv.identity),
+            (684, 1368, 4, 95.0)
+        );
+        assert_eq!(inv.stvs[0].to_string(), "S1C1/5/19H1L.4-1");
+    }
+
+    #[test]
+    fn test_no_inversion_between_arrays() {
+        use Strand::{Minus, Plus};
+        // Three arrays separated by gaps with the middle array in the opposite orientation.
+        let mut mons = monomers(&[
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+            (4, Minus, 100.0),
+            (3, Minus, 100.0),
+            (2, Minus, 100.0),
+            (1, Minus, 100.0),
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+        ]);
+        for (i, mon) in mons.get_mut("chr1").unwrap().iter_mut().enumerate() {
+            let offset = (i as u64 / 4) * 10_000;
+            (mon.0, mon.1) = (mon.0 + offset, mon.1 + offset);
+        }
+        assert!(find_inversions(&mons, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_no_inversion_at_ends() {
+        use Strand::{Minus, Plus};
+        let mons = monomers(&[
+            (4, Minus, 100.0),
+            (3, Minus, 100.0),
+            (2, Minus, 100.0),
+            (1, Minus, 100.0),
+            (1, Plus, 100.0),
+            (2, Plus, 100.0),
+            (3, Plus, 100.0),
+            (4, Plus, 100.0),
+            (5, Plus, 100.0),
+        ]);
+        assert!(find_inversions(&mons, None).unwrap().is_empty());
+    }
+}
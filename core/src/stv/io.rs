@@ -1,34 +1,493 @@
-use std::{collections::HashMap, io::BufRead, path::Path, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{BufRead, Write},
+    path::Path,
+    str::FromStr,
+};
 
 use itertools::Itertools;
 
 use crate::{Monomer, Strand, HOR};
 
-use super::monomers_to_hor;
+use super::{
+    monomers_to_hor,
+    region::{split_region, Region},
+    report::{ReadReport, SkipReason, SkippedRecord},
+    validate::BedIssueKind,
+};
 
-/// A `BED9` HOR monomer record.
-/// ```no_run
+/// A `BED9` HOR monomer record with any extra columns.
 ///
-/// let record = ("chr1", 1, 170, "S1C1/5/19H1L.6", 100.0, "+", 1, 170, "0,0,0");
+/// # Examples
+/// ```
+/// use rs_asat_hor::{MonomerRecord, Strand};
+///
+/// let line = "chr1\t1\t170\tS1C1/5/19H1L.6\t100.0\t+\t1\t170\t0,0,0\tS1C1/5/19H1L.5\t1e-50";
+/// let record: MonomerRecord = line.parse().unwrap();
+/// assert_eq!(record.monomer.to_string(), "S1C1/5/19H1L.6");
+/// assert_eq!(record.strand, Strand::Plus);
+/// assert_eq!(record.extra, ["S1C1/5/19H1L.5", "1e-50"]);
+/// assert_eq!(record.to_string(), line);
 /// ```
-pub type MonomerRecord<'a> = (&'a str, u64, u64, &'a str, f32, &'a str, u64, u64, &'a str);
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonomerRecord {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    /// Monomer with its [`Monomer::strand`] set to `strand`.
+    pub monomer: Monomer,
+    /// Score column as read. See [`MonomerRecord::identity`].
+    score: String,
+    pub strand: Strand,
+    pub thick_st: u64,
+    pub thick_end: u64,
+    /// itemRgb column as read. ex. `0,0,0`
+    pub rgb: String,
+    /// Columns after the 9th, as is. ex. A secondary label or e-value.
+    pub extra: Vec<String>,
+}
+
+impl MonomerRecord {
+    /// Parse the columns of a record. The name is checked first so unparsable monomers can be skipped.
+    fn from_columns(columns: &[&str]) -> Result<Self, BedIssueKind> {
+        let [chrom, st, end, name, identity, strand, thick_st, thick_end, rgb, extra @ ..] =
+            columns
+        else {
+            return Err(BedIssueKind::WrongColumnCount(columns.len()));
+        };
+        let monomer =
+            Monomer::new(name).map_err(|_| BedIssueKind::UnparsableName(name.to_string()))?;
+        fn parse_num<T: FromStr>(col: usize, value: &str) -> Result<T, BedIssueKind> {
+            value
+                .parse()
+                .map_err(|_| BedIssueKind::InvalidNumber(col, value.to_owned()))
+        }
+        let strand = Strand::from_str(strand)
+            .map_err(|_| BedIssueKind::InvalidStrand(strand.to_string()))?;
+        Ok(Self {
+            chrom: chrom.to_string(),
+            st: parse_num(2, st)?,
+            end: parse_num(3, end)?,
+            monomer: monomer.with_strand(strand),
+            score: parse_num::<f32>(5, identity).map(|_| identity.to_string())?,
+            strand,
+            thick_st: parse_num(7, thick_st)?,
+            thick_end: parse_num(8, thick_end)?,
+            rgb: rgb.to_string(),
+            extra: extra.iter().map(|col| col.to_string()).collect(),
+        })
+    }
+}
+
+impl MonomerRecord {
+    /// Score column. Percent identity in HumAS-HMMER output.
+    pub fn identity(&self) -> f32 {
+        // Checked when parsed or set.
+        self.score.parse().unwrap_or(f32::NAN)
+    }
+
+    /// Set the score column. The original score text is replaced.
+    ///
+    /// ```
+    /// use rs_asat_hor::MonomerRecord;
+    ///
+    /// let mut record: MonomerRecord = "chr1\t0\t171\tS2C15H1L.1\t100.00\t+\t0\t171\t0,0,0"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(record.identity(), 100.0);
+    /// record.set_identity(98.5);
+    /// assert_eq!(record.to_string(), "chr1\t0\t171\tS2C15H1L.1\t98.5\t+\t0\t171\t0,0,0");
+    /// ```
+    pub fn set_identity(&mut self, identity: f32) {
+        self.score = identity.to_string();
+    }
+}
+
+impl FromStr for MonomerRecord {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let columns = s.trim_end_matches(['\r', '\n']).split('\t').collect_vec();
+        MonomerRecord::from_columns(&columns).map_err(|kind| eyre::eyre!("{kind}"))
+    }
+}
+
+/// Write as a tab-delimited `BED9` line followed by any extra columns.
+impl Display for MonomerRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.chrom,
+            self.st,
+            self.end,
+            self.monomer,
+            self.score,
+            self.strand,
+            self.thick_st,
+            self.thick_end,
+            self.rgb
+        )?;
+        for col in &self.extra {
+            write!(f, "\t{col}")?;
+        }
+        Ok(())
+    }
+}
 
 /// An `BED4` HOR structural variation record.
-/// ```no_run
-/// use rs_asat_hor::HOR;
+/// ```
+/// use rs_asat_hor::{HOR, StvRecord};
+///
+/// let record = StvRecord::new("chr1", 1, 1020, HOR::new("S1C1/5/19H1L.1-6").unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StvRecord {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    pub hor: HOR,
+    /// [`Region`] of the parent contig this record was shifted out of.
+    /// * Only set if in the parent contig's frame. See [`StvRecord::to_parent_frame`].
+    pub region: Option<Region>,
+}
+
+/// [`Monomer`]s with their `(st, end, identity, monomer)` grouped by contig.
+pub type ContigMonomers = HashMap<String, Vec<(u64, u64, f32, Monomer)>>;
+
+/// Coordinate frame of contigs named as a [`Region`], `name:start-end`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoordFrame {
+    /// Coordinates are relative to the contig as named.
+    #[default]
+    Contig,
+    /// Coordinates are shifted into the frame of the parent contig, `name`.
+    Parent,
+}
+
+impl StvRecord {
+    /// Construct a new [`StvRecord`] with coordinates relative to `chrom`.
+    pub fn new(chrom: impl Into<String>, st: u64, end: u64, hor: HOR) -> Self {
+        Self {
+            chrom: chrom.into(),
+            st,
+            end,
+            hor,
+            region: None,
+        }
+    }
+
+    /// Get the [`Strand`] of this record from its first monomer.
+    ///
+    /// ```
+    /// use rs_asat_hor::{read_from_monomer_bed, Strand};
+    ///
+    /// let records = read_from_monomer_bed("test/mons.bed", |_| false).unwrap();
+    /// assert_eq!(records[0].strand(), Some(Strand::Minus));
+    /// ```
+    pub fn strand(&self) -> Option<Strand> {
+        self.hor.monomers().first().and_then(|mon| mon.strand)
+    }
+
+    /// Get the name of the parent contig.
+    /// * If `chrom` is not formatted as a [`Region`], this is `chrom`.
+    ///
+    /// ```
+    /// use rs_asat_hor::{HOR, StvRecord};
+    ///
+    /// let hor = HOR::new("S2C15H1L.11-1").unwrap();
+    /// let record = StvRecord::new("chm1_chr15:3977696-8919402", 2732060, 2733936, hor);
+    /// assert_eq!(record.parent_chrom(), "chm1_chr15");
+    /// ```
+    pub fn parent_chrom(&self) -> &str {
+        if let Some(region) = self.region.as_ref() {
+            return &region.name;
+        }
+        split_region(&self.chrom)
+            .map(|(name, _, _)| name)
+            .unwrap_or(&self.chrom)
+    }
+
+    /// Shift this record into the frame of its parent contig.
+    /// * Noop if `chrom` is not formatted as a [`Region`] or already in the parent contig's frame.
+    ///
+    /// ```
+    /// use rs_asat_hor::{HOR, StvRecord};
+    ///
+    /// let hor = HOR::new("S2C15H1L.11-1").unwrap();
+    /// let record = StvRecord::new("chm1_chr15:3977696-8919402", 2732060, 2733936, hor);
+    /// let parent_record = record.clone().to_parent_frame();
+    /// assert_eq!(
+    ///     (parent_record.chrom.as_str(), parent_record.st, parent_record.end),
+    ///     ("chm1_chr15", 6709756, 6711632)
+    /// );
+    /// assert_eq!(parent_record.to_region_frame(), record);
+    /// ```
+    pub fn to_parent_frame(mut self) -> Self {
+        if self.region.is_some() {
+            return self;
+        }
+        let Ok(region) = Region::from_str(&self.chrom) else {
+            return self;
+        };
+        self.chrom = region.name.clone();
+        self.st = region.to_parent(self.st);
+        self.end = region.to_parent(self.end);
+        self.region = Some(region);
+        self
+    }
+
+    /// Shift this record back into the frame of the [`Region`] it came from.
+    /// * Noop if not in the parent contig's frame.
+    pub fn to_region_frame(mut self) -> Self {
+        let Some(region) = self.region.take() else {
+            return self;
+        };
+        self.st = region.to_relative(self.st);
+        self.end = region.to_relative(self.end);
+        self.chrom = region.to_string();
+        self
+    }
+
+    /// Shift this record into the given [`CoordFrame`].
+    pub fn to_frame(self, frame: CoordFrame) -> Self {
+        match frame {
+            CoordFrame::Contig => self.to_region_frame(),
+            CoordFrame::Parent => self.to_parent_frame(),
+        }
+    }
+}
+
+/// Read a `BED9` file of [`MonomerRecord`]s in file order.
+/// * Lines with fewer than 9 columns are skipped. Extra columns are kept in [`MonomerRecord::extra`].
+/// * Records with names that can't be parsed as a [`Monomer`] are logged and skipped.
+/// * Use [`read_monomer_records_with_report`] to skip invalid records and report them instead.
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `fn_filter`
+///     * Function to filter records if `true`.
+///     * A noop can be achieved with `|_| false`
+///
+/// # Returns
+/// * [`MonomerRecord`]s or an error if a field is invalid.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomer_records, MonomerRecord};
+///
+/// let records = read_monomer_records("test/mons.bed", |rec: &MonomerRecord| rec.identity() < 99.0).unwrap();
+/// assert_eq!(records.len(), 17);
+/// ```
+pub fn read_monomer_records<F>(
+    bedfile: impl AsRef<Path>,
+    fn_filter: F,
+) -> eyre::Result<Vec<MonomerRecord>>
+where
+    F: Fn(&MonomerRecord) -> bool,
+{
+    let (records, report) = read_monomer_records_with_report(bedfile, fn_filter)?;
+    for skipped in report.skipped.iter() {
+        match &skipped.reason {
+            SkipReason::Filtered | SkipReason::Invalid(BedIssueKind::WrongColumnCount(_)) => {}
+            SkipReason::Invalid(BedIssueKind::UnparsableName(_)) => {
+                log::error!("{skipped} Skipping.")
+            }
+            SkipReason::Invalid(_) => eyre::bail!("{skipped}"),
+        }
+    }
+    Ok(records)
+}
+
+/// Read a `BED9` file of [`MonomerRecord`]s in file order, skipping invalid records.
+/// * Empty lines and lines starting with `#` are ignored.
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `fn_filter`
+///     * Function to filter records if `true`.
+///     * A noop can be achieved with `|_| false`
+///
+/// # Returns
+/// * Valid [`MonomerRecord`]s and a [`ReadReport`] of the skipped lines, or an error if the file can't be read.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomer_records_with_report, MonomerRecord};
 ///
-/// let record = (String::from("chr1"), 1, 1020, HOR::new("S1C1/5/19H1L.1-6"));
+/// let (records, report) = read_monomer_records_with_report(
+///     "test/mons.bed",
+///     |rec: &MonomerRecord| rec.identity() < 99.0,
+/// )
+/// .unwrap();
+/// assert_eq!(records.len(), 17);
+/// assert_eq!(report.counts()["filtered"], 5);
+/// assert!(!report.has_failures());
 /// ```
-pub type StvRecord = (String, u64, u64, HOR);
+pub fn read_monomer_records_with_report<F>(
+    bedfile: impl AsRef<Path>,
+    fn_filter: F,
+) -> eyre::Result<(Vec<MonomerRecord>, ReadReport)>
+where
+    F: Fn(&MonomerRecord) -> bool,
+{
+    let file = std::fs::File::open(bedfile)?;
+    let fh = std::io::BufReader::new(file);
+
+    let mut records = vec![];
+    let mut report = ReadReport::default();
+    for (i, line) in fh.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let columns = line.split('\t').collect_vec();
+        let chrom = columns[0];
+        let reason = match MonomerRecord::from_columns(&columns) {
+            // Allow filter function.
+            Ok(record) if fn_filter(&record) => SkipReason::Filtered,
+            Ok(record) => {
+                *report.read_bp.entry(record.chrom.clone()).or_default() +=
+                    record.end.saturating_sub(record.st);
+                report.n_read += 1;
+                records.push(record);
+                continue;
+            }
+            Err(kind) => SkipReason::Invalid(kind),
+        };
+        let span = columns
+            .get(1)
+            .zip(columns.get(2))
+            .and_then(|(st, end)| Some((st.parse().ok()?, end.parse().ok()?)));
+        report.skipped.push(SkippedRecord {
+            line: i + 1,
+            chrom: chrom.to_owned(),
+            span,
+            reason,
+        });
+    }
+    Ok((records, report))
+}
+
+/// Group [`MonomerRecord`]s by contig, keeping their order. See [`ContigMonomers`].
+pub fn group_monomer_records<'a>(
+    records: impl IntoIterator<Item = &'a MonomerRecord>,
+) -> ContigMonomers {
+    let mut chr_mons: ContigMonomers = HashMap::new();
+    for rec in records {
+        chr_mons.entry(rec.chrom.clone()).or_default().push((
+            rec.st,
+            rec.end,
+            rec.identity(),
+            rec.monomer.clone(),
+        ));
+    }
+    chr_mons
+}
+
+/// Read a `BED9` file of [`MonomerRecord`]s into [`Monomer`]s grouped by contig.
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `fn_filter`
+///     * Function to filter records if `true`.
+///     * A noop can be achieved with `|_| false`
+///
+/// # Returns
+/// * [`Monomer`]s with their `(st, end)` coordinates and identity by contig, in file order.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::read_monomers_from_bed;
+///
+/// let monomers = read_monomers_from_bed("test/mons.bed", |_| false).unwrap();
+/// assert_eq!(monomers["chm1_chr15:3977696-8919402"].len(), 22);
+/// ```
+pub fn read_monomers_from_bed<F>(
+    bedfile: impl AsRef<Path>,
+    fn_filter: F,
+) -> eyre::Result<ContigMonomers>
+where
+    F: Fn(&MonomerRecord) -> bool,
+{
+    let records = read_monomer_records(bedfile, fn_filter)?;
+    Ok(group_monomer_records(&records))
+}
+
+/// Convert [`Monomer`]s ordered by position on a contig to [`StvRecord`]s.
+/// * Monomers are `(st, end, identity, monomer)`.
+pub(crate) fn monomers_to_stv_records(
+    chrom: &str,
+    mons: &[(u64, u64, f32, Monomer)],
+) -> eyre::Result<Vec<StvRecord>> {
+    let mut records: Vec<StvRecord> = vec![];
+
+    // Convert monomers in chromosome to HOR.
+    // We don't enforce strand here or chunk to avoid breaking HORs.
+    let hors = monomers_to_hor(mons.iter().map(|m| &m.3), None)?;
+
+    // Keep track of monomer index positions with cumulative sum of indices.
+    // ex.
+    //    mon: 1 2 3 7 8
+    //    hor: 0 0 0 1 1
+    //    idx: 0 1 2 3 4
+    // res.
+    //    [0, 3, 5]
+    let mut idxs_mon = vec![0; hors.len() + 1];
+
+    for (i, idx_mon) in hors
+        .iter()
+        .map(|h| h.n_monomers())
+        .enumerate()
+        // Offset by 1 for starting position 0.
+        .map(|(i, m)| (i + 1, m))
+    {
+        // Safe as always i < idxs_mon.
+        let idx_mon_offset = idxs_mon.get(i - 1).unwrap();
+        idxs_mon[i] = idx_mon + idx_mon_offset
+    }
+
+    // Convert to idx intervals.
+    // ex.  [0, 3, 5]
+    // res. (0, 3), (3, 5)
+    for ((st, end), hor) in idxs_mon
+        .into_iter()
+        .tuple_windows::<(usize, usize)>()
+        .zip(hors.into_iter())
+    {
+        let Some(mons) = mons.get(st..end) else {
+            continue;
+        };
+        // Find min and max coordinates of HOR.
+        let mut min_st = u64::MAX;
+        let mut max_end = 0;
+        for (st, end, _, _) in mons {
+            min_st = std::cmp::min(min_st, *st);
+            max_end = std::cmp::max(max_end, *end);
+        }
+        assert!(
+            min_st != u64::MAX,
+            "Logic error with indexing with {chrom}:{st}-{end} and {hor}. Report on GitHub issue tracker."
+        );
+        records.push(StvRecord::new(chrom, min_st, max_end, hor));
+    }
+    Ok(records)
+}
 
 /// Read a `BED9` file of [`MonomerRecord`]s and convert them to [`StvRecord`]s.
+/// * Coordinates are kept in the frame of each contig. See [`read_from_monomer_bed_in_frame`].
 ///
 /// # Args
 /// * `bedfile`
 ///     * Path to `BED9` file.
 /// * `fn_filter`
 ///     * Function to filter records if `true`.
-///     * A noop can be achieved with `|_| true`
+///     * A noop can be achieved with `|_| false`
 ///
 /// # Returns
 /// * Valid [`StvRecord`]s
@@ -40,7 +499,7 @@ pub type StvRecord = (String, u64, u64, HOR);
 ///
 /// let records = read_from_monomer_bed(
 ///     "test/mons.bed",
-///     |rec: MonomerRecord| rec.4 < 85.0
+///     |rec: &MonomerRecord| rec.identity() < 85.0
 /// );
 /// assert!(records.is_ok())
 /// ```
@@ -49,104 +508,223 @@ pub fn read_from_monomer_bed<F>(
     fn_filter: F,
 ) -> eyre::Result<Vec<StvRecord>>
 where
-    F: Fn(MonomerRecord) -> bool,
+    F: Fn(&MonomerRecord) -> bool,
 {
-    let file = std::fs::File::open(bedfile).unwrap();
-    let fh = std::io::BufReader::new(file);
+    read_from_monomer_bed_in_frame(bedfile, fn_filter, CoordFrame::Contig)
+}
+
+/// Read a `BED9` file of [`MonomerRecord`]s and convert them to [`StvRecord`]s in a given [`CoordFrame`].
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `fn_filter`
+///     * Function to filter records if `true`.
+///     * A noop can be achieved with `|_| false`
+/// * `frame`
+///     * [`CoordFrame`] of output records for contigs named as a [`Region`].
+///
+/// # Returns
+/// * Valid [`StvRecord`]s
+///
+/// # Examples
+/// Shift records from contigs named `name:start-end` into the frame of `name`.
+/// ```
+/// use rs_asat_hor::{read_from_monomer_bed_in_frame, CoordFrame};
+///
+/// let records = read_from_monomer_bed_in_frame("test/mons.bed", |_| false, CoordFrame::Parent).unwrap();
+/// assert_eq!(records[0].chrom, "chm1_chr15");
+/// assert_eq!(records[0].st, 3977696 + 2732060);
+/// ```
+pub fn read_from_monomer_bed_in_frame<F>(
+    bedfile: impl AsRef<Path>,
+    fn_filter: F,
+    frame: CoordFrame,
+) -> eyre::Result<Vec<StvRecord>>
+where
+    F: Fn(&MonomerRecord) -> bool,
+{
+    let chr_mons = read_monomers_from_bed(bedfile, fn_filter)?;
+    contig_monomers_to_stv_records(&chr_mons, frame)
+}
+
+/// Read a `BED9` file of [`MonomerRecord`]s and convert them to [`StvRecord`]s, skipping invalid records.
+/// * See [`read_monomer_records_with_report`] and [`read_from_monomer_bed`].
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `fn_filter`
+///     * Function to filter records if `true`.
+///     * A noop can be achieved with `|_| false`
+/// * `frame`
+///     * [`CoordFrame`] of output records for contigs named as a [`Region`].
+///
+/// # Returns
+/// * [`StvRecord`]s and a [`ReadReport`] of the skipped lines.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_from_monomer_bed_with_report, CoordFrame};
+///
+/// let (records, report) =
+///     read_from_monomer_bed_with_report("test/mons.bed", |_| false, CoordFrame::Contig).unwrap();
+/// assert_eq!(records.len(), 2);
+/// assert!(report.skipped.is_empty());
+/// ```
+pub fn read_from_monomer_bed_with_report<F>(
+    bedfile: impl AsRef<Path>,
+    fn_filter: F,
+    frame: CoordFrame,
+) -> eyre::Result<(Vec<StvRecord>, ReadReport)>
+where
+    F: Fn(&MonomerRecord) -> bool,
+{
+    let (records, report) = read_monomer_records_with_report(bedfile, fn_filter)?;
+    let chr_mons = group_monomer_records(&records);
+    Ok((contig_monomers_to_stv_records(&chr_mons, frame)?, report))
+}
+
+/// Convert [`Monomer`]s grouped by contig to [`StvRecord`]s.
+/// * Use to convert monomers after processing them. ex. With [`crate::resolve_overlaps`].
+///
+/// # Args
+/// * `chr_mons`
+///     * [`ContigMonomers`] ordered by position.
+/// * `frame`
+///     * [`CoordFrame`] of output records for contigs named as a [`Region`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{contig_monomers_to_stv_records, read_monomers_from_bed, resolve_overlaps, CoordFrame};
+///
+/// let mut monomers = read_monomers_from_bed("test/mons.bed", |_| false).unwrap();
+/// let discarded = resolve_overlaps(&mut monomers, None);
+/// assert!(discarded.is_empty());
+/// let records = contig_monomers_to_stv_records(&monomers, CoordFrame::Contig).unwrap();
+/// assert_eq!(records.len(), 2);
+/// ```
+pub fn contig_monomers_to_stv_records(
+    chr_mons: &ContigMonomers,
+    frame: CoordFrame,
+) -> eyre::Result<Vec<StvRecord>> {
     let mut records: Vec<StvRecord> = vec![];
 
-    let mut chr_mons: HashMap<String, Vec<(u64, u64, Monomer)>> = HashMap::new();
-    for line in fh.lines() {
-        let line = line?;
-        let Some((chrom, st, end, name, score, ort, tst, tend, rgb)) =
-            line.trim().split('\t').collect_tuple()
-        else {
-            continue;
-        };
-        let (st, end, score, tst, tend) = (
-            st.parse::<u64>()?,
-            end.parse::<u64>()?,
-            score.parse::<f32>()?,
-            tst.parse::<u64>()?,
-            tend.parse::<u64>()?,
+    for (chrom, mons) in chr_mons.iter() {
+        records.extend(
+            monomers_to_stv_records(chrom, mons)?
+                .into_iter()
+                .map(|rec| rec.to_frame(frame)),
         );
+    }
+    Ok(records)
+}
 
-        // Allow filter function.
-        if fn_filter((chrom, st, end, name, score, ort, tst, tend, rgb)) {
-            continue;
-        }
+/// Write [`StvRecord`]s as a `BED4` file.
+///
+/// # Args
+/// * `output`
+///     * Writer for `BED4` records.
+/// * `records`
+///     * [`StvRecord`]s to write.
+/// * `frame`
+///     * [`CoordFrame`] of written records for contigs named as a [`Region`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{write_to_stv_bed, CoordFrame, StvRecord, HOR};
+///
+/// let hor = HOR::new("S2C15H1L.11-1").unwrap();
+/// let records = [StvRecord::new("chm1_chr15:3977696-8919402", 2732060, 2733936, hor)];
+///
+/// let mut output = vec![];
+/// write_to_stv_bed(&mut output, &records, CoordFrame::Parent).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "chm1_chr15\t6709756\t6711632\tS2C15H1L.11-1\n"
+/// );
+/// ```
+pub fn write_to_stv_bed<'a>(
+    mut output: impl Write,
+    records: impl IntoIterator<Item = &'a StvRecord>,
+    frame: CoordFrame,
+) -> eyre::Result<()> {
+    for record in records {
+        let record = record.clone().to_frame(frame);
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}",
+            record.chrom, record.st, record.end, record.hor
+        )?;
+    }
+    Ok(())
+}
 
-        // Add strand.
-        let strand = Strand::from_str(ort)?;
-        if let Ok(mon) = Monomer::new(name).map(|m| m.with_strand(strand)) {
-            if let Some(mons) = chr_mons.get_mut(chrom) {
-                mons.push((st, end, mon));
-            } else {
-                chr_mons
-                    .entry(chrom.to_owned())
-                    .or_insert(vec![(st, end, mon)]);
-            }
-        } else {
-            log::error!("Cannot convert monomer ({name}) at {chrom}:{st}-{end}. Skipping.");
-        }
+/// Write [`MonomerRecord`]s as a `BED9` file, keeping any extra columns.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomer_records, write_monomer_records};
+///
+/// let records = read_monomer_records("test/mons.bed", |_| false).unwrap();
+/// let mut output = vec![];
+/// write_monomer_records(&mut output, &records).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap().lines().next(),
+///     Some("chm1_chr15:3977696-8919402\t2732060\t2732230\tS2C15H1L.11\t100.00\t-\t2732060\t2732230\t0,0,0")
+/// );
+/// ```
+pub fn write_monomer_records<'a>(
+    mut output: impl Write,
+    records: impl IntoIterator<Item = &'a MonomerRecord>,
+) -> eyre::Result<()> {
+    for record in records {
+        writeln!(output, "{record}")?;
     }
-    for (chrom, mons) in chr_mons.iter() {
-        // Convert monomers in chromosome to HOR.
-        // We don't enforce strand here or chunk to avoid breaking HORs.
-        let hors = monomers_to_hor(mons.iter().map(|m| &m.2), None)?;
-
-        // Keep track of monomer index positions with cumulative sum of indices.
-        // ex.
-        //    mon: 1 2 3 7 8
-        //    hor: 0 0 0 1 1
-        //    idx: 0 1 2 3 4
-        // res.
-        //    [0, 3, 5]
-        let mut idxs_mon = vec![0; hors.len() + 1];
-
-        for (i, idx_mon) in hors
-            .iter()
-            .map(|h| h.n_monomers())
-            .enumerate()
-            // Offset by 1 for starting position 0.
-            .map(|(i, m)| (i + 1, m))
-        {
-            // Safe as always i < idxs_mon.
-            let idx_mon_offset = idxs_mon.get(i - 1).unwrap();
-            idxs_mon[i] = idx_mon + idx_mon_offset
-        }
+    Ok(())
+}
 
-        // Convert to idx intervals.
-        // ex.  [0, 3, 5]
-        // res. (0, 3), (3, 5)
-        for ((st, end), hor) in idxs_mon
-            .into_iter()
-            .tuple_windows::<(usize, usize)>()
-            .zip(hors.into_iter())
-        {
-            let Some(mons) = mons.get(st..end) else {
-                continue;
-            };
-            // Find min and max coordinates of HOR.
-            let mut min_st = u64::MAX;
-            let mut max_end = 0;
-            for (st, end, _) in mons {
-                min_st = std::cmp::min(min_st, *st);
-                max_end = std::cmp::max(max_end, *end);
-            }
-            assert!(
-                min_st != u64::MAX,
-                "Logic error with indexing with {chrom}:{st}-{end} and {hor}. Report on GitHub issue tracker."
-            );
-            records.push((chrom.to_string(), min_st, max_end, hor));
+/// Write [`Monomer`]s grouped by contig as a `BED9` file of [`MonomerRecord`]s.
+/// * Contigs are written in sorted order and monomers in their given order.
+/// * Monomers without a [`Strand`] are written on the [`Strand::Plus`].
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use rs_asat_hor::{write_to_monomer_bed, Monomer};
+///
+/// let mon = Monomer::new("S2C15H1L.11").unwrap();
+/// let monomers = HashMap::from([(String::from("chr15"), vec![(0, 170, 99.5, mon)])]);
+///
+/// let mut output = vec![];
+/// write_to_monomer_bed(&mut output, &monomers).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "chr15\t0\t170\tS2C15H1L.11\t99.50\t+\t0\t170\t0,0,0\n"
+/// );
+/// ```
+pub fn write_to_monomer_bed(mut output: impl Write, monomers: &ContigMonomers) -> eyre::Result<()> {
+    for (chrom, mons) in monomers.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+        for (st, end, identity, mon) in mons {
+            writeln!(
+                output,
+                "{chrom}\t{st}\t{end}\t{mon}\t{identity:.2}\t{}\t{st}\t{end}\t0,0,0",
+                mon.strand.unwrap_or(Strand::Plus)
+            )?;
         }
     }
-    Ok(records)
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{read_from_monomer_bed, Monomer, Strand, HOR};
+    use itertools::Itertools;
+
+    use crate::{
+        read_from_monomer_bed, read_from_monomer_bed_in_frame, read_monomer_records,
+        read_monomer_records_with_report, write_to_stv_bed, CoordFrame, Monomer, MonomerRecord,
+        Region, Strand, StvRecord, HOR,
+    };
 
     #[test]
     fn test_read_mon_bed() {
@@ -166,14 +744,14 @@ mod test {
         assert_eq!(
             records,
             vec![
-                (
-                    String::from("chm1_chr15:3977696-8919402"),
+                StvRecord::new(
+                    "chm1_chr15:3977696-8919402",
                     2732060,
                     2733936,
                     exp_hor[0].clone()
                 ),
-                (
-                    String::from("chm1_chr15:3977696-8919402"),
+                StvRecord::new(
+                    "chm1_chr15:3977696-8919402",
                     2734617,
                     2736493,
                     exp_hor[1].clone()
@@ -185,7 +763,7 @@ mod test {
     #[test]
     fn test_read_mon_bed_filter() {
         // Filter less than 99% identity.
-        let records = read_from_monomer_bed("test/mons.bed", |row| row.4 < 99.0).unwrap();
+        let records = read_from_monomer_bed("test/mons.bed", |row| row.identity() < 99.0).unwrap();
 
         let mut monomers: Vec<Monomer> = (4..12)
             .map(|i| {
@@ -206,20 +784,20 @@ mod test {
         assert_eq!(
             records,
             vec![
-                (
-                    String::from("chm1_chr15:3977696-8919402"),
+                StvRecord::new(
+                    "chm1_chr15:3977696-8919402",
                     2732060,
                     2733427,
                     exp_hor[0].clone()
                 ),
-                (
-                    String::from("chm1_chr15:3977696-8919402"),
+                StvRecord::new(
+                    "chm1_chr15:3977696-8919402",
                     2734617,
                     2735984,
                     exp_hor[1].clone()
                 ),
-                (
-                    String::from("chm1_chr15:3977696-8919402"),
+                StvRecord::new(
+                    "chm1_chr15:3977696-8919402",
                     2736323,
                     2736493,
                     exp_hor[2].clone()
@@ -227,4 +805,138 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_read_mon_bed_parent_frame() {
+        let records =
+            read_from_monomer_bed_in_frame("test/mons.bed", |_| false, CoordFrame::Parent).unwrap();
+        let region = Region::new("chm1_chr15:3977696-8919402").unwrap();
+
+        assert_eq!(
+            records
+                .iter()
+                .map(|rec| (rec.chrom.as_str(), rec.st, rec.end, rec.parent_chrom()))
+                .collect_vec(),
+            [
+                ("chm1_chr15", 6709756, 6711632, "chm1_chr15"),
+                ("chm1_chr15", 6712313, 6714189, "chm1_chr15"),
+            ]
+        );
+        assert!(records
+            .iter()
+            .all(|rec| rec.region.as_ref() == Some(&region)));
+
+        // Restore original coordinates.
+        let contig_records = read_from_monomer_bed("test/mons.bed", |_| false).unwrap();
+        assert_eq!(
+            records
+                .into_iter()
+                .map(|rec| rec.to_region_frame())
+                .collect_vec(),
+            contig_records
+        );
+    }
+
+    #[test]
+    fn test_write_stv_bed() {
+        let records = read_from_monomer_bed("test/mons.bed", |_| false).unwrap();
+
+        let mut output = vec![];
+        write_to_stv_bed(&mut output, &records, CoordFrame::Contig).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            [
+                "chm1_chr15:3977696-8919402\t2732060\t2733936\tS2C15H1L.11-1\n",
+                "chm1_chr15:3977696-8919402\t2734617\t2736493\tS2C15H1L.11-1\n",
+            ]
+            .join("")
+        );
+
+        let mut output = vec![];
+        write_to_stv_bed(&mut output, &records, CoordFrame::Parent).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            [
+                "chm1_chr15\t6709756\t6711632\tS2C15H1L.11-1\n",
+                "chm1_chr15\t6712313\t6714189\tS2C15H1L.11-1\n",
+            ]
+            .join("")
+        );
+    }
+
+    #[test]
+    fn test_monomer_record_parse() {
+        let rec: MonomerRecord = "chr1\t0\t171\tS2C15H1L.1\t98.5\t-\t0\t171\t255,0,0\tS2C15H1L.2"
+            .parse()
+            .unwrap();
+        assert_eq!(rec.rgb, "255,0,0");
+        assert_eq!(rec.monomer.strand, Some(Strand::Minus));
+        assert_eq!(rec.extra, ["S2C15H1L.2"]);
+
+        let rec: MonomerRecord = "chr1\t0\t171\tS2C15H1L.1\t98.5\t+\t0\t171\t0"
+            .parse()
+            .unwrap();
+        assert_eq!(rec.rgb, "0");
+        assert!(rec.extra.is_empty());
+
+        // Score and itemRgb are written back as is.
+        for line in [
+            "chr1\t0\t171\tS2C15H1L.1\t100.00\t+\t0\t171\t.",
+            "chr1\t0\t171\tS2C15H1L.1\t1e2\t+\t0\t171\t255,0,0,255",
+        ] {
+            let mut rec: MonomerRecord = line.parse().unwrap();
+            assert_eq!(rec.identity(), 100.0);
+            assert_eq!(rec.to_string(), line);
+            rec.set_identity(98.5);
+            assert!(rec.to_string().contains("\t98.5\t"));
+        }
+
+        for line in [
+            "chr1\t0\t171\tS2C15H1L.1\t98.5\t+\t0\t171",
+            "chr1\t0\t171\tS2C15H1L.1\t98.5\t.\t0\t171\t0,0,0",
+            "chr1\tx\t171\tS2C15H1L.1\t98.5\t+\t0\t171\t0,0,0",
+            "chr1\t0\t171\tnot_a_monomer\t98.5\t+\t0\t171\t0,0,0",
+        ] {
+            assert!(line.parse::<MonomerRecord>().is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_read_with_report() {
+        let bed = "\
+# header
+chr1\t0\t171\tS2C15H1L.1\t98.5\t+\t0\t171\t0,0,0
+chr1\t171\t342\tS2C15H1L.2\t98.5\t.\t171\t342\t0,0,0
+chr1\t342\t513\tnot_a_monomer\t98.5\t+\t342\t513\t0,0,0
+chr1\t513\t684\tS2C15H1L.4\t80.0\t+\t513\t684\t0,0,0
+chr1\t684
+";
+        let path = std::env::temp_dir().join("test_read_with_report.bed");
+        std::fs::write(&path, bed).unwrap();
+        let (records, report) =
+            read_monomer_records_with_report(&path, |rec| rec.identity() < 90.0).unwrap();
+        let res_strict = read_monomer_records(&path, |_| false);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(report.n_read, 1);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|rec| (rec.line, rec.reason.label()))
+                .collect_vec(),
+            [
+                (3, "invalid_strand"),
+                (4, "unparsable_name"),
+                (5, "filtered"),
+                (6, "wrong_column_count")
+            ]
+        );
+        assert_eq!(report.skipped_spans()["chr1"], [(171, 684)]);
+        assert_eq!(report.fraction_skipped_bp("chr1"), 0.75);
+        assert!(report.has_failures());
+        // Invalid strand is an error when strict.
+        assert!(res_strict.is_err());
+    }
 }
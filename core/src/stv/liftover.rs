@@ -0,0 +1,634 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use eyre::{bail, ContextCompat};
+use itertools::Itertools;
+use rust_lapper::{Interval, Lapper};
+
+use crate::Strand;
+
+use super::{
+    io::{MonomerRecord, StvRecord},
+    region::Region,
+};
+
+/// An ungapped aligned block of a [`Chain`].
+/// * Query coordinates are on the chain's query strand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChainBlock {
+    t_start: u64,
+    q_start: u64,
+    size: u64,
+}
+
+/// A UCSC chain between a target (source) and query (destination) assembly.
+/// * See <https://genome.ucsc.edu/goldenPath/help/chain.html>
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub score: f64,
+    pub t_name: String,
+    pub t_size: u64,
+    pub t_start: u64,
+    pub t_end: u64,
+    pub q_name: String,
+    pub q_size: u64,
+    pub q_strand: Strand,
+    pub q_start: u64,
+    pub q_end: u64,
+    pub id: Option<String>,
+    blocks: Vec<ChainBlock>,
+}
+
+impl FromStr for Chain {
+    type Err = eyre::Error;
+
+    /// Parse a chain header line. Blocks must be added after.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect_vec();
+        let Some((
+            "chain",
+            score,
+            t_name,
+            t_size,
+            t_strand,
+            t_start,
+            t_end,
+            q_name,
+            q_size,
+            q_strand,
+            q_start,
+            q_end,
+        )) = fields.iter().take(12).cloned().collect_tuple()
+        else {
+            bail!("Invalid chain header, {s}.")
+        };
+        if Strand::from_str(t_strand)? != Strand::Plus {
+            bail!("Invalid chain header, {s}. Target strand must be '+'.")
+        }
+        let chain = Chain {
+            score: score.parse()?,
+            t_name: t_name.to_owned(),
+            t_size: t_size.parse()?,
+            t_start: t_start.parse()?,
+            t_end: t_end.parse()?,
+            q_name: q_name.to_owned(),
+            q_size: q_size.parse()?,
+            q_strand: Strand::from_str(q_strand)?,
+            q_start: q_start.parse()?,
+            q_end: q_end.parse()?,
+            id: fields.get(12).map(|id| id.to_string()),
+            blocks: vec![],
+        };
+        if chain.t_start > chain.t_end || chain.t_end > chain.t_size {
+            bail!("Invalid chain header, {s}. Target coordinates are outside of target size.")
+        }
+        if chain.q_start > chain.q_end || chain.q_end > chain.q_size {
+            bail!("Invalid chain header, {s}. Query coordinates are outside of query size.")
+        }
+        Ok(chain)
+    }
+}
+
+impl Chain {
+    /// Lift an interval on the target through this chain.
+    /// * Returns the lifted interval on the `+` strand of the query.
+    fn lift(&self, st: u64, end: u64) -> Option<LiftedInterval> {
+        let mut min_st = u64::MAX;
+        let mut max_end = 0;
+        let mut mapped = vec![];
+        for block in self.blocks.iter() {
+            let block_end = block.t_start + block.size;
+            if block_end <= st || block.t_start >= end {
+                continue;
+            }
+            let ovl_st = std::cmp::max(st, block.t_start);
+            let ovl_end = std::cmp::min(end, block_end);
+            let q_st = block.q_start + (ovl_st - block.t_start);
+            let q_end = q_st + (ovl_end - ovl_st);
+            // Query coordinates of (-) strand chains are on the reverse complement.
+            let (q_st, q_end) = match self.q_strand {
+                Strand::Plus => (q_st, q_end),
+                Strand::Minus => (self.q_size - q_end, self.q_size - q_st),
+            };
+            min_st = std::cmp::min(min_st, q_st);
+            max_end = std::cmp::max(max_end, q_end);
+            mapped.push((ovl_st, ovl_end));
+        }
+        (!mapped.is_empty()).then(|| LiftedInterval {
+            chrom: self.q_name.clone(),
+            st: min_st,
+            end: max_end,
+            strand: self.q_strand,
+            n_mapped: mapped.iter().map(|(st, end)| end - st).sum(),
+            mapped,
+        })
+    }
+}
+
+/// An interval lifted through a single [`Chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiftedInterval {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    /// Strand of the query relative to the target.
+    pub strand: Strand,
+    /// Number of bases of the original interval mapped.
+    pub n_mapped: u64,
+    /// Intervals of the original interval mapped as `(st, end)`.
+    pub mapped: Vec<(u64, u64)>,
+}
+
+/// A [`StvRecord`] lifted over with a [`ChainMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiftedStvRecord {
+    /// Original record.
+    pub source: StvRecord,
+    /// Lifted records. One for each chain the source maps to.
+    /// * Records on the opposite strand have their [`crate::HOR`] reversed and monomer strands flipped.
+    pub lifted: Vec<StvRecord>,
+    /// Lifted intervals. Same order as `lifted`.
+    pub intervals: Vec<LiftedInterval>,
+}
+
+impl LiftedStvRecord {
+    /// Record doesn't map.
+    pub fn is_unmapped(&self) -> bool {
+        self.lifted.is_empty()
+    }
+
+    /// Record maps to more than one chain.
+    pub fn is_split(&self) -> bool {
+        self.lifted.len() > 1
+    }
+
+    /// Not all bases of the record map.
+    /// * Bases mapped by more than one chain are only counted once.
+    pub fn is_partial(&self) -> bool {
+        let mut n_mapped = 0;
+        let mut merged: Option<(u64, u64)> = None;
+        for (st, end) in self
+            .intervals
+            .iter()
+            .flat_map(|itv| itv.mapped.iter().cloned())
+            .sorted()
+        {
+            merged = match merged {
+                Some((merged_st, merged_end)) if st <= merged_end => {
+                    Some((merged_st, merged_end.max(end)))
+                }
+                Some((merged_st, merged_end)) => {
+                    n_mapped += merged_end - merged_st;
+                    Some((st, end))
+                }
+                None => Some((st, end)),
+            };
+        }
+        n_mapped += merged.map_or(0, |(st, end)| end - st);
+        n_mapped < self.source.end - self.source.st
+    }
+
+    /// Record maps to the opposite strand on any chain.
+    pub fn is_strand_changed(&self) -> bool {
+        self.intervals.iter().any(|itv| itv.strand == Strand::Minus)
+    }
+
+    /// Labels of how this record maps. ex. `split`, `partial` or `strand_changed`
+    pub fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.is_unmapped(), "unmapped"),
+            (self.is_split(), "split"),
+            (self.is_partial(), "partial"),
+            (self.is_strand_changed(), "strand_changed"),
+        ]
+        .into_iter()
+        .filter_map(|(is_flag, flag)| is_flag.then_some(flag))
+        .collect()
+    }
+}
+
+/// An index of UCSC [`Chain`]s by target contig.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{ChainMap, Strand};
+///
+/// let chains = "chain 1000 chr1 1000 + 0 1000 chr1_new 1100 + 100 1100 1\n1000\n\n";
+/// let chain_map = ChainMap::from_reader(chains.as_bytes()).unwrap();
+///
+/// let lifted = chain_map.lift("chr1", 10, 20);
+/// assert_eq!((lifted[0].st, lifted[0].end, lifted[0].strand), (110, 120, Strand::Plus));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChainMap {
+    chains: Vec<Chain>,
+    index: HashMap<String, Lapper<u64, usize>>,
+}
+
+impl ChainMap {
+    /// Read a UCSC chain file.
+    pub fn new(chainfile: impl AsRef<Path>) -> eyre::Result<Self> {
+        let file = std::fs::File::open(chainfile)?;
+        ChainMap::from_reader(BufReader::new(file))
+    }
+
+    /// Read UCSC chains from a reader.
+    pub fn from_reader(reader: impl BufRead) -> eyre::Result<Self> {
+        let mut chains: Vec<Chain> = vec![];
+        // Current position in target and query of last chain.
+        let mut curr_pos: Option<(u64, u64)> = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with("chain") {
+                let chain = Chain::from_str(line)?;
+                curr_pos = Some((chain.t_start, chain.q_start));
+                chains.push(chain);
+                continue;
+            }
+            let (Some(chain), Some((t_pos, q_pos))) = (chains.last_mut(), curr_pos.as_mut()) else {
+                bail!("Alignment data without chain header at line {}.", i + 1)
+            };
+            let mut fields = line.split_whitespace();
+            let size = fields
+                .next()
+                .with_context(|| format!("No block size at line {}.", i + 1))?
+                .parse::<u64>()?;
+            // Blocks past the chain end would lift outside of the query.
+            if *t_pos + size > chain.t_end || *q_pos + size > chain.q_end {
+                bail!(
+                    "Alignment block at line {} extends past chain end ({}:{} or {}:{}).",
+                    i + 1,
+                    chain.t_name,
+                    chain.t_end,
+                    chain.q_name,
+                    chain.q_end
+                )
+            }
+            chain.blocks.push(ChainBlock {
+                t_start: *t_pos,
+                q_start: *q_pos,
+                size,
+            });
+            // Last line of chain has no gaps.
+            let (dt, dq) = match (fields.next(), fields.next()) {
+                (Some(dt), Some(dq)) => (dt.parse::<u64>()?, dq.parse::<u64>()?),
+                (None, None) => (0, 0),
+                _ => bail!("Invalid alignment data, {line}, at line {}.", i + 1),
+            };
+            *t_pos += size + dt;
+            *q_pos += size + dq;
+        }
+
+        let mut chain_itvs: HashMap<String, Vec<Interval<u64, usize>>> = HashMap::new();
+        for (i, chain) in chains.iter().enumerate() {
+            chain_itvs
+                .entry(chain.t_name.clone())
+                .or_default()
+                .push(Interval {
+                    start: chain.t_start,
+                    stop: chain.t_end,
+                    val: i,
+                });
+        }
+        Ok(ChainMap {
+            chains,
+            index: chain_itvs
+                .into_iter()
+                .map(|(chrom, itvs)| (chrom, Lapper::new(itvs)))
+                .collect(),
+        })
+    }
+
+    /// Get all [`Chain`]s.
+    pub fn chains(&self) -> &[Chain] {
+        &self.chains
+    }
+
+    /// Lift an interval through all overlapping [`Chain`]s.
+    /// * Use for per-monomer intervals like [`crate::MonomerRecord`]s.
+    /// * Returns one [`LiftedInterval`] per chain, ordered by chain score.
+    pub fn lift(&self, chrom: &str, st: u64, end: u64) -> Vec<LiftedInterval> {
+        let Some(chain_idxs) = self.index.get(chrom) else {
+            return vec![];
+        };
+        chain_idxs
+            .find(st, end)
+            .map(|itv| &self.chains[itv.val])
+            .sorted_by(|a, b| b.score.total_cmp(&a.score))
+            .filter_map(|chain| chain.lift(st, end))
+            .collect()
+    }
+
+    /// Lift a [`StvRecord`] through all overlapping [`Chain`]s.
+    /// * Records named as a [`crate::Region`] should be shifted to their parent frame first.
+    ///
+    /// ```
+    /// use rs_asat_hor::{ChainMap, StvRecord, HOR};
+    ///
+    /// let chains = "chain 1000 chr1 1000 + 0 1000 chr1_new 1000 - 0 1000 1\n1000\n\n";
+    /// let chain_map = ChainMap::from_reader(chains.as_bytes()).unwrap();
+    ///
+    /// let record = StvRecord::new("chr1", 0, 340, HOR::new("S1C1/5/19H1L.1-2").unwrap());
+    /// let lifted = chain_map.lift_stv(&record);
+    /// assert!(lifted.is_strand_changed());
+    /// assert_eq!(lifted.lifted[0].hor, HOR::new("S1C1/5/19H1L.2-1").unwrap());
+    /// assert_eq!((lifted.lifted[0].st, lifted.lifted[0].end), (660, 1000));
+    /// ```
+    pub fn lift_stv(&self, record: &StvRecord) -> LiftedStvRecord {
+        let intervals = self.lift(&record.chrom, record.st, record.end);
+        let lifted = intervals
+            .iter()
+            .map(|itv| {
+                let hor = match itv.strand {
+                    Strand::Plus => record.hor.clone(),
+                    Strand::Minus => {
+                        let mut hor = record.hor.reversed();
+                        for mon in hor.monomers.iter_mut() {
+                            mon.strand = mon.strand.map(|strand| lifted_strand(strand, itv.strand));
+                        }
+                        hor
+                    }
+                };
+                StvRecord::new(itv.chrom.clone(), itv.st, itv.end, hor)
+            })
+            .collect();
+        LiftedStvRecord {
+            source: record.clone(),
+            lifted,
+            intervals,
+        }
+    }
+
+    /// Lift a [`MonomerRecord`] through all overlapping [`Chain`]s.
+    /// * Contigs named as a [`Region`] are shifted to their parent frame first.
+    /// * Records on the opposite strand have their strand flipped.
+    ///
+    /// ```
+    /// use rs_asat_hor::{ChainMap, MonomerRecord, Strand};
+    ///
+    /// let chains = "chain 1000 chr1 1000 + 0 1000 chr1_new 1000 - 0 1000 1\n1000\n\n";
+    /// let chain_map = ChainMap::from_reader(chains.as_bytes()).unwrap();
+    ///
+    /// let record: MonomerRecord = "chr1:100-500\t0\t171\tS1C1/5/19H1L.1\t99.0\t+\t0\t171\t0,0,0"
+    ///     .parse()
+    ///     .unwrap();
+    /// let lifted = chain_map.lift_monomer(&record);
+    /// assert_eq!((lifted[0].st, lifted[0].end, lifted[0].strand), (729, 900, Strand::Minus));
+    /// ```
+    pub fn lift_monomer(&self, record: &MonomerRecord) -> Vec<MonomerRecord> {
+        let (chrom, st, end) = match Region::from_str(&record.chrom) {
+            Ok(region) => (
+                region.name.clone(),
+                region.to_parent(record.st),
+                region.to_parent(record.end),
+            ),
+            Err(_) => (record.chrom.clone(), record.st, record.end),
+        };
+        self.lift(&chrom, st, end)
+            .into_iter()
+            .map(|itv| {
+                let strand = lifted_strand(record.strand, itv.strand);
+                let mut lifted = record.clone();
+                lifted.monomer = lifted.monomer.with_strand(strand);
+                lifted.strand = strand;
+                (lifted.st, lifted.end) = (itv.st, itv.end);
+                (lifted.thick_st, lifted.thick_end) = (itv.st, itv.end);
+                lifted.chrom = itv.chrom;
+                lifted
+            })
+            .collect()
+    }
+}
+
+/// Strand of a feature on `strand` after lifting through a chain on `chain_strand`.
+fn lifted_strand(strand: Strand, chain_strand: Strand) -> Strand {
+    match (strand, chain_strand) {
+        (strand, Strand::Plus) => strand,
+        (Strand::Plus, Strand::Minus) => Strand::Minus,
+        (Strand::Minus, Strand::Minus) => Strand::Plus,
+    }
+}
+
+/// Write [`LiftedStvRecord`]s as a `BED4+2` file. Unmapped records are skipped.
+/// * Columns are `chrom`, `st`, `end`, lifted StV, source record as `chrom:st-end` and [`LiftedStvRecord::flags`] delimited by `,` or `.` if none.
+///
+/// # Args
+/// * `output`
+///     * Writer for `BED` records.
+/// * `records`
+///     * [`LiftedStvRecord`]s to write.
+pub fn write_to_lifted_bed<'a>(
+    mut output: impl Write,
+    records: impl IntoIterator<Item = &'a LiftedStvRecord>,
+) -> eyre::Result<()> {
+    for record in records {
+        let flags = record.flags();
+        let flags = if flags.is_empty() {
+            String::from(".")
+        } else {
+            flags.join(",")
+        };
+        for lifted in record.lifted.iter() {
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}:{}-{}\t{flags}",
+                lifted.chrom,
+                lifted.st,
+                lifted.end,
+                lifted.hor,
+                record.source.chrom,
+                record.source.st,
+                record.source.end
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{write_to_lifted_bed, ChainMap, Monomer, MonomerRecord, Strand, StvRecord, HOR};
+
+    // chr1:0-100 -> chr1_new:50-150 (+)
+    // chr1:100-120 is deleted.
+    // chr1:120-200 -> chr1_new:150-230 (+)
+    // chr1:200-300 -> chr2_new:0-100 (-)
+    const CHAINS: &str = "\
+chain 5000 chr1 300 + 0 200 chr1_new 500 + 50 230 1
+100 20 0
+80
+
+chain 1000 chr1 300 + 200 300 chr2_new 400 - 300 400 2
+100
+";
+
+    #[test]
+    fn test_parse_chains() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        assert_eq!(chain_map.chains().len(), 2);
+        assert_eq!(chain_map.chains()[0].id.as_deref(), Some("1"));
+        assert_eq!(chain_map.chains()[1].q_strand, Strand::Minus);
+    }
+
+    #[test]
+    fn test_invalid_chains() {
+        assert!(ChainMap::from_reader("100 20 0\n".as_bytes()).is_err());
+        assert!(
+            ChainMap::from_reader("chain 1 chr1 300 - 0 1 chr1 1 + 0 1\n1\n".as_bytes()).is_err()
+        );
+        assert!(ChainMap::from_reader("chain 1 chr1 300\n".as_bytes()).is_err());
+        // Query end past query size.
+        assert!(ChainMap::from_reader(
+            "chain 1 chr1 300 + 0 100 chr1 50 - 0 100\n100\n".as_bytes()
+        )
+        .is_err());
+        // Truncated chain with blocks past the chain end.
+        assert!(ChainMap::from_reader(
+            "chain 1 chr1 300 + 0 100 chr1 100 - 0 100\n80 0 0\n80\n".as_bytes()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_lift_full() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        let record = StvRecord::new("chr1", 10, 90, HOR::new("S1C1/5/19H1L.1-6").unwrap());
+        let lifted = chain_map.lift_stv(&record);
+
+        assert!(!lifted.is_unmapped() && !lifted.is_split() && !lifted.is_partial());
+        assert!(!lifted.is_strand_changed());
+        assert_eq!(
+            lifted.lifted,
+            [StvRecord::new(
+                "chr1_new",
+                60,
+                140,
+                HOR::new("S1C1/5/19H1L.1-6").unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_lift_partial() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        let record = StvRecord::new("chr1", 90, 130, HOR::new("S1C1/5/19H1L.1-6").unwrap());
+        let lifted = chain_map.lift_stv(&record);
+
+        assert!(lifted.is_partial() && !lifted.is_split());
+        assert_eq!(lifted.intervals[0].n_mapped, 20);
+        assert_eq!((lifted.lifted[0].st, lifted.lifted[0].end), (140, 160));
+    }
+
+    #[test]
+    fn test_lift_partial_overlapping_chains() {
+        // chr1:0-100 maps to both chr1_new and chr2_new.
+        let chains = "\
+chain 2000 chr1 200 + 0 100 chr1_new 100 + 0 100 1
+100
+
+chain 1000 chr1 200 + 0 100 chr2_new 100 + 0 100 2
+100
+";
+        let chain_map = ChainMap::from_reader(chains.as_bytes()).unwrap();
+        let record = StvRecord::new("chr1", 50, 150, HOR::new("S1C1/5/19H1L.1-6").unwrap());
+        let lifted = chain_map.lift_stv(&record);
+
+        assert!(lifted.is_split() && lifted.is_partial());
+        assert_eq!(
+            lifted
+                .intervals
+                .iter()
+                .map(|itv| itv.mapped.clone())
+                .collect::<Vec<_>>(),
+            [[(50, 100)], [(50, 100)]]
+        );
+    }
+
+    #[test]
+    fn test_lift_split_strand() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        let record = StvRecord::new("chr1", 150, 250, HOR::new("S1C1/5/19H1L.1-6").unwrap());
+        let lifted = chain_map.lift_stv(&record);
+
+        assert!(lifted.is_split() && lifted.is_strand_changed() && !lifted.is_partial());
+        assert_eq!(
+            lifted.lifted,
+            [
+                StvRecord::new("chr1_new", 180, 230, HOR::new("S1C1/5/19H1L.1-6").unwrap()),
+                StvRecord::new("chr2_new", 50, 100, HOR::new("S1C1/5/19H1L.6-1").unwrap()),
+            ]
+        );
+
+        let hor = HOR::from_monomers(&[
+            Monomer::new("S1C1/5/19H1L.1")
+                .unwrap()
+                .with_strand(Strand::Plus),
+            Monomer::new("S1C1/5/19H1L.2")
+                .unwrap()
+                .with_strand(Strand::Plus),
+        ])
+        .unwrap()
+        .remove(0);
+        let record = StvRecord::new("chr1", 200, 250, hor);
+        let lifted = chain_map.lift_stv(&record);
+        assert_eq!(record.strand(), Some(Strand::Plus));
+        assert_eq!(lifted.lifted[0].strand(), Some(Strand::Minus));
+        assert_eq!(lifted.lifted[0].hor.to_string(), "S1C1/5/19H1L.2-1");
+    }
+
+    #[test]
+    fn test_write_lifted_bed() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        let lifted = [
+            StvRecord::new("chr1", 10, 90, HOR::new("S1C1/5/19H1L.1-6").unwrap()),
+            StvRecord::new("chr1", 150, 250, HOR::new("S1C1/5/19H1L.1-6").unwrap()),
+            StvRecord::new("chr1", 100, 120, HOR::new("S1C1/5/19H1L.1-6").unwrap()),
+        ]
+        .iter()
+        .map(|rec| chain_map.lift_stv(rec))
+        .collect::<Vec<_>>();
+        assert_eq!(lifted[2].flags(), ["unmapped", "partial"]);
+
+        let mut output = vec![];
+        write_to_lifted_bed(&mut output, &lifted).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\
+chr1_new\t60\t140\tS1C1/5/19H1L.1-6\tchr1:10-90\t.
+chr1_new\t180\t230\tS1C1/5/19H1L.1-6\tchr1:150-250\tsplit,strand_changed
+chr2_new\t50\t100\tS1C1/5/19H1L.6-1\tchr1:150-250\tsplit,strand_changed
+"
+        );
+    }
+
+    #[test]
+    fn test_lift_monomer() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        let record: MonomerRecord =
+            "chr1\t200\t250\tS1C1/5/19H1L.1\t99.0\t-\t200\t250\t0,0,0\tS1C1/5/19H1L.2"
+                .parse()
+                .unwrap();
+        let lifted = chain_map.lift_monomer(&record);
+        assert_eq!(
+            lifted.iter().map(|rec| rec.to_string()).collect::<Vec<_>>(),
+            ["chr2_new\t50\t100\tS1C1/5/19H1L.1\t99.0\t+\t50\t100\t0,0,0\tS1C1/5/19H1L.2"]
+        );
+        assert_eq!(lifted[0].monomer.strand, Some(Strand::Plus));
+    }
+
+    #[test]
+    fn test_lift_unmapped() {
+        let chain_map = ChainMap::from_reader(CHAINS.as_bytes()).unwrap();
+        assert!(chain_map.lift("chr1", 100, 120).is_empty());
+        assert!(chain_map.lift("chr2", 0, 10).is_empty());
+    }
+}
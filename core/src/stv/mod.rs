@@ -1,5 +1,48 @@
+mod align;
+mod annotate;
+mod array;
+mod chimera;
+mod divergence;
+mod dotplot;
+mod fasta;
+mod index;
+mod inversion;
 mod io;
+mod liftover;
 mod mon_to_hor;
+mod overlap;
+mod query;
+mod region;
+mod relabel;
+mod report;
+mod simulate;
+#[cfg(test)]
+mod test_utils;
+mod validate;
 
-pub use io::{read_from_monomer_bed, MonomerRecord, StvRecord};
+pub use align::{align_stvs, AlignedStv, AlignmentMode, AlignmentScoring, StvAlignment};
+pub use annotate::{annotate_monomers, annotate_region, AnnotationOpts};
+pub use array::{divergent_flanks, find_hor_arrays, ArrayFlanks, ArrayOpts, HORArray};
+pub use chimera::{ChimeraCatalogue, ChimericMonomer, Junction, RecurrentJunction};
+pub use divergence::{hor_copy_divergence, write_to_divergence_bed, DivergenceOpts, StvDivergence};
+pub use dotplot::SelfSimilarity;
+pub use fasta::{write_monomer_fasta, write_stv_fasta, IndexedFasta};
+pub use index::{SharedMonomers, StvHit, StvIndex};
+pub use inversion::{find_inversions, write_to_inversion_bed, Inversion, InversionOpts};
+pub use io::{
+    contig_monomers_to_stv_records, group_monomer_records, read_from_monomer_bed,
+    read_from_monomer_bed_in_frame, read_from_monomer_bed_with_report, read_monomer_records,
+    read_monomer_records_with_report, read_monomers_from_bed, write_monomer_records,
+    write_to_monomer_bed, write_to_stv_bed, ContigMonomers, CoordFrame, MonomerRecord, StvRecord,
+};
+pub use liftover::{write_to_lifted_bed, Chain, ChainMap, LiftedInterval, LiftedStvRecord};
 pub use mon_to_hor::monomers_to_hor;
+pub use overlap::{resolve_overlaps, DiscardedMonomer, OverlapOpts, OverlapPolicy};
+pub use query::StvQuery;
+pub use region::Region;
+pub use relabel::{relabel_contig_monomers, relabel_monomers, RelabelOpts, RelabelledMonomer};
+pub use report::{ReadReport, SkipReason, SkippedRecord};
+pub use simulate::{simulate_array, SimulatedArray, SimulationOpts};
+pub use validate::{
+    validate_monomer_bed, validate_monomer_bed_reader, BedIssue, BedIssueKind, ValidationOpts,
+};
@@ -2,10 +2,13 @@ use eyre::bail;
 
 use crate::{
     as_hor::{hor_monomer_structure_to_monomers, MonomerUnit},
-    Monomer, Strand, HOR,
+    RepeatUnit, Strand, HOR,
 };
 
-fn get_hor_num(start_mon: Option<&Monomer>, current_num: &u8) -> eyre::Result<MonomerUnit> {
+fn get_hor_num<M: RepeatUnit>(
+    start_mon: Option<&M>,
+    current_num: &u8,
+) -> eyre::Result<MonomerUnit> {
     let Some(Some(start_num)) = start_mon.map(|mon| mon.right_most_num()) else {
         bail!("Start ({start_mon:?}) monomer number not found.")
     };
@@ -18,8 +21,25 @@ fn get_hor_num(start_mon: Option<&Monomer>, current_num: &u8) -> eyre::Result<Mo
     }
 }
 
-/// Convert a sequence of [`Monomer`]s into a [`HOR`].
+/// Check if the HOR breaks between two consecutive monomer numbers on a strand.
+pub(crate) fn is_hor_break(mon_1_num: u8, mon_2_num: u8, strand: Strand) -> bool {
+    // > > x >
+    // 5 6 - 1
+    let is_gap = mon_1_num.abs_diff(mon_2_num) > 1;
+    let is_broken = match strand {
+        // > x > >
+        // 6 - 5 6
+        Strand::Plus => mon_1_num > mon_2_num,
+        // < x < <
+        // 5 - 6 5
+        Strand::Minus => mon_1_num < mon_2_num,
+    };
+    is_gap || is_broken
+}
+
+/// Convert a sequence of [`crate::Monomer`]s into a [`HOR`].
 /// * This assumes that the input sequence has been chunked by strand, gaps, and chrom name.
+/// * Any [`RepeatUnit`] can be used in place of [`crate::Monomer`].
 ///
 /// ```
 /// use rs_asat_hor::{Monomer, Strand, HOR, monomers_to_hor};
@@ -35,10 +55,14 @@ fn get_hor_num(start_mon: Option<&Monomer>, current_num: &u8) -> eyre::Result<Mo
 ///     HOR::new("S1C1/5/19H1L.1-3").unwrap(),
 /// )
 /// ```
-pub fn monomers_to_hor<'a, M>(monomers: M, enforce_strand: Option<Strand>) -> eyre::Result<Vec<HOR>>
+pub fn monomers_to_hor<'a, M, I>(
+    monomers: I,
+    enforce_strand: Option<Strand>,
+) -> eyre::Result<Vec<HOR<M>>>
 where
-    M: Iterator<Item = &'a Monomer>,
-    M: ExactSizeIterator,
+    M: RepeatUnit + 'a,
+    I: Iterator<Item = &'a M>,
+    I: ExactSizeIterator,
 {
     let mut hors = Vec::new();
     if monomers.len() <= 1 {
@@ -47,12 +71,11 @@ where
 
     let mut monomers_iter = monomers.into_iter().peekable();
     // Create a base monomer to clone.
-    let mut monomer_base = monomers_iter.peek().cloned().unwrap().clone();
-    monomer_base.monomers.clear();
+    let monomer_base = monomers_iter.peek().cloned().unwrap().with_numbers(&[]);
 
     // Bookkeeping vars.
     // Keep track of start and store units. Clear when add new HOR.
-    let mut start_mon: Option<&Monomer> = None;
+    let mut start_mon: Option<&M> = None;
     let mut hor_units: Vec<MonomerUnit> = Vec::new();
 
     while let Some(mon_1) = monomers_iter.next() {
@@ -67,7 +90,7 @@ where
         let Some(mon_2) = monomers_iter.peek() else {
             // Add remainder once hit end of iterator.
             let final_hor_unit = if mon_1_chimeric {
-                MonomerUnit::Chimera(mon_1.monomers.to_vec())
+                MonomerUnit::Chimera(mon_1.numbers().to_vec())
             } else {
                 get_hor_num(start_mon, mon_1_num)?
             };
@@ -80,21 +103,9 @@ where
         // Check if want to enforce strand.
         // If not, get strand based on mon_2.
         // If no strand provided, assume forward ort.
-        let strand = enforce_strand.unwrap_or_else(|| mon_2.strand.unwrap_or(Strand::Plus));
-
-        // > > x >
-        // 5 6 - 1
-        let is_gap = mon_1_num.abs_diff(*mon_2_num) > 1;
-        let is_broken = match strand {
-            // > x > >
-            // 6 - 5 6
-            Strand::Plus => mon_1 > mon_2,
-            // < x < <
-            // 5 - 6 5
-            Strand::Minus => mon_1 < mon_2,
-        };
+        let strand = enforce_strand.unwrap_or_else(|| mon_2.strand().unwrap_or(Strand::Plus));
 
-        if is_gap || is_broken {
+        if is_hor_break(*mon_1_num, *mon_2_num, strand) {
             // Case 1: Gap in range.
             // > >  > x  >
             // 1 2 *3 - *6
@@ -102,7 +113,7 @@ where
             // > >  > x  >
             // 4 5 *6 - *1
             let hor_unit = if mon_1_chimeric {
-                MonomerUnit::Chimera(mon_1.monomers.to_vec())
+                MonomerUnit::Chimera(mon_1.numbers().to_vec())
             } else {
                 get_hor_num(start_mon, mon_1_num)?
             };
@@ -128,7 +139,7 @@ where
             hor_units.push(get_hor_num(start_mon, mon_1_num)?);
 
             // Add chimeric monomer.
-            hor_units.push(MonomerUnit::Chimera(mon_2.monomers.to_vec()));
+            hor_units.push(MonomerUnit::Chimera(mon_2.numbers().to_vec()));
 
             // Clear start mon.
             // And consumer chimeric mon.
@@ -150,10 +161,34 @@ where
 mod test {
     use itertools::Itertools;
 
-    use crate::{Monomer, HOR};
+    use crate::{Monomer, RepeatUnit, HOR};
 
     use super::{monomers_to_hor, Strand};
 
+    #[derive(Debug, Clone, PartialEq)]
+    struct HSat3 {
+        numbers: Vec<u8>,
+        strand: Option<Strand>,
+    }
+
+    impl RepeatUnit for HSat3 {
+        fn numbers(&self) -> &[u8] {
+            &self.numbers
+        }
+        fn strand(&self) -> Option<Strand> {
+            self.strand
+        }
+        fn with_numbers(&self, numbers: &[u8]) -> Self {
+            HSat3 {
+                numbers: numbers.to_vec(),
+                strand: self.strand,
+            }
+        }
+        fn repeat_name(&self) -> String {
+            String::from("HSat3")
+        }
+    }
+
     fn assert_hors_equal(
         hors: impl Iterator<Item = HOR>,
         exp_hors: impl Iterator<Item = HOR>,
@@ -322,4 +357,25 @@ mod test {
             true,
         );
     }
+
+    #[test]
+    fn test_stv_generic_unit() {
+        let units = [[3], [2], [1], [3]]
+            .into_iter()
+            .map(|num| HSat3 {
+                numbers: num.to_vec(),
+                strand: Some(Strand::Minus),
+            })
+            .chain([HSat3 {
+                numbers: vec![1, 2],
+                strand: Some(Strand::Minus),
+            }])
+            .collect_vec();
+        let hors = monomers_to_hor(units.iter(), None).unwrap();
+        assert_eq!(
+            hors.iter().map(|hor| hor.to_string()).collect_vec(),
+            ["HSat3.3-1", "HSat3.3_1/2"]
+        );
+        assert_eq!(hors[1].n_monomers(), 2);
+    }
 }
@@ -0,0 +1,347 @@
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+use crate::{Monomer, Status, Strand};
+
+use super::io::ContigMonomers;
+
+/// Policy to pick between two overlapping monomer calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Keep the call with the higher identity.
+    BestIdentity,
+    /// Keep the longer call.
+    Longest,
+    /// Keep the call from a live HOR. See [`Status::Live`].
+    PreferLive,
+    /// Keep the call on the same strand as the previous kept call, or the contig's majority strand if none.
+    ConsistentStrand,
+}
+
+/// Options for [`resolve_overlaps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapOpts {
+    /// Policies applied in order until one call is preferred.
+    /// * If all policies tie, the earlier call by position and then file order is kept.
+    pub policies: Vec<OverlapPolicy>,
+    /// Minimum overlap, as a fraction of the shorter call, for two calls to conflict.
+    pub min_overlap: f32,
+}
+
+impl Default for OverlapOpts {
+    fn default() -> Self {
+        Self {
+            policies: vec![
+                OverlapPolicy::BestIdentity,
+                OverlapPolicy::ConsistentStrand,
+                OverlapPolicy::PreferLive,
+                OverlapPolicy::Longest,
+            ],
+            min_overlap: 0.5,
+        }
+    }
+}
+
+/// A monomer call discarded by [`resolve_overlaps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscardedMonomer {
+    pub chrom: String,
+    pub st: u64,
+    pub end: u64,
+    pub identity: f32,
+    pub monomer: Monomer,
+    /// The overlapping call kept instead as `(st, end, identity, monomer)`.
+    pub kept: (u64, u64, f32, Monomer),
+    /// Policy that decided between the calls. `None` if all policies tied.
+    pub policy: Option<OverlapPolicy>,
+}
+
+type Call = (u64, u64, f32, Monomer);
+
+/// Compare two calls by a policy. [`Ordering::Greater`] if `a` is preferred.
+fn compare_calls(policy: OverlapPolicy, a: &Call, b: &Call, strand: Option<Strand>) -> Ordering {
+    match policy {
+        OverlapPolicy::BestIdentity => a.2.total_cmp(&b.2),
+        OverlapPolicy::Longest => (a.1 - a.0).cmp(&(b.1 - b.0)),
+        OverlapPolicy::PreferLive => {
+            let fn_live = |call: &Call| call.3.status == Some(Status::Live);
+            fn_live(a).cmp(&fn_live(b))
+        }
+        OverlapPolicy::ConsistentStrand => {
+            let fn_consistent = |call: &Call| strand.is_some() && call.3.strand == strand;
+            fn_consistent(a).cmp(&fn_consistent(b))
+        }
+    }
+}
+
+/// Get the most common strand of calls, if any.
+fn majority_strand(calls: &[Call]) -> Option<Strand> {
+    let counts = calls.iter().filter_map(|call| call.3.strand).counts();
+    counts
+        .into_iter()
+        // Break ties towards the plus strand.
+        .max_by_key(|(strand, n)| (*n, *strand == Strand::Plus))
+        .map(|(strand, _)| strand)
+}
+
+/// Resolve overlapping and duplicate monomer calls in place, keeping one call per conflict.
+/// * Calls are sorted by position and each is compared to every kept call it conflicts with.
+///     * A call replaces kept calls only if preferred over all of them.
+/// * Use before converting monomers to StVs as overlapping calls break HORs.
+///
+/// # Args
+/// * `monomers`
+///     * [`ContigMonomers`] to resolve. See [`crate::read_monomers_from_bed`].
+/// * `opts`
+///     * [`OverlapOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`DiscardedMonomer`]s by contig and position.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use rs_asat_hor::{resolve_overlaps, Monomer, OverlapPolicy};
+///
+/// let mon_1 = Monomer::new("S2C15H1L.1").unwrap();
+/// let mon_2 = Monomer::new("S2C15H1L.2").unwrap();
+/// let mut monomers = HashMap::from([(
+///     String::from("chr15"),
+///     vec![(0, 171, 99.0, mon_1.clone()), (0, 171, 90.0, mon_2), (171, 342, 99.0, mon_1)],
+/// )]);
+/// let discarded = resolve_overlaps(&mut monomers, None);
+/// assert_eq!(monomers["chr15"].len(), 2);
+/// assert_eq!(discarded[0].monomer.to_string(), "S2C15H1L.2");
+/// assert_eq!(discarded[0].policy, Some(OverlapPolicy::BestIdentity));
+/// ```
+pub fn resolve_overlaps(
+    monomers: &mut ContigMonomers,
+    opts: Option<OverlapOpts>,
+) -> Vec<DiscardedMonomer> {
+    let opts = opts.unwrap_or_default();
+    let mut discarded = vec![];
+    for (chrom, calls) in monomers.iter_mut().sorted_by(|a, b| a.0.cmp(b.0)) {
+        let contig_strand = majority_strand(calls);
+        // Stable so duplicates are kept in file order.
+        calls.sort_by_key(|call| (call.0, call.1));
+
+        let mut kept: Vec<Call> = Vec::with_capacity(calls.len());
+        // Longest kept call. Kept calls starting this far before a call can't overlap it.
+        let mut max_len = 0;
+        for call in calls.drain(..) {
+            let fn_is_conflict = |other: &Call| {
+                let overlap = std::cmp::min(other.1, call.1).saturating_sub(call.0);
+                let min_len = std::cmp::min(other.1 - other.0, call.1 - call.0);
+                overlap > 0 && (min_len == 0 || overlap as f32 / min_len as f32 >= opts.min_overlap)
+            };
+            // Nearest first.
+            let conflicts = kept
+                .iter()
+                .enumerate()
+                .rev()
+                .take_while(|(_, other)| other.0 + max_len > call.0)
+                .filter_map(|(i, other)| fn_is_conflict(other).then_some(i))
+                .collect_vec();
+            if conflicts.is_empty() {
+                max_len = std::cmp::max(max_len, call.1 - call.0);
+                kept.push(call);
+                continue;
+            }
+
+            let strand = kept
+                .iter()
+                .enumerate()
+                .rev()
+                .find(|(i, _)| !conflicts.contains(i))
+                .and_then(|(_, call)| call.3.strand)
+                .or(contig_strand);
+            // Call must be preferred over every conflicting kept call to replace them.
+            let mut replaced = vec![];
+            let mut winner = None;
+            for i in conflicts {
+                let (policy, ord) = opts
+                    .policies
+                    .iter()
+                    .map(|policy| {
+                        (
+                            Some(*policy),
+                            compare_calls(*policy, &call, &kept[i], strand),
+                        )
+                    })
+                    .find(|(_, ord)| ord.is_ne())
+                    .unwrap_or((None, Ordering::Equal));
+                if ord.is_gt() {
+                    replaced.push((i, policy));
+                } else {
+                    winner = Some((i, policy));
+                    break;
+                }
+            }
+
+            let fn_discard = |discard: Call, keep: &Call, policy| DiscardedMonomer {
+                chrom: chrom.clone(),
+                st: discard.0,
+                end: discard.1,
+                identity: discard.2,
+                monomer: discard.3,
+                kept: keep.clone(),
+                policy,
+            };
+            if let Some((i, policy)) = winner {
+                discarded.push(fn_discard(call, &kept[i], policy));
+                continue;
+            }
+            // Indices are descending so removing doesn't shift the rest.
+            for (i, policy) in replaced {
+                let discard = kept.remove(i);
+                discarded.push(fn_discard(discard, &call, policy));
+            }
+            max_len = std::cmp::max(max_len, call.1 - call.0);
+            kept.push(call);
+        }
+        *calls = kept;
+    }
+    discarded
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{resolve_overlaps, Monomer, OverlapOpts, OverlapPolicy, Strand};
+
+    fn call(
+        st: u64,
+        end: u64,
+        identity: f32,
+        name: &str,
+        strand: Strand,
+    ) -> (u64, u64, f32, Monomer) {
+        (
+            st,
+            end,
+            identity,
+            Monomer::new(name).unwrap().with_strand(strand),
+        )
+    }
+
+    fn labels(calls: &[(u64, u64, f32, Monomer)]) -> Vec<String> {
+        calls
+            .iter()
+            .map(|(st, end, _, mon)| format!("{st}-{end}:{mon}({})", mon.strand.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_duplicates() {
+        let dup = call(0, 171, 99.0, "S2C15H1L.1", Strand::Plus);
+        let mut monomers = HashMap::from([(String::from("chr1"), vec![dup.clone(), dup.clone()])]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(monomers["chr1"], [dup]);
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(discarded[0].policy, None);
+    }
+
+    #[test]
+    fn test_resolve_small_overlap_kept() {
+        let mut monomers = HashMap::from([(
+            String::from("chr1"),
+            vec![
+                call(0, 171, 99.0, "S2C15H1L.1", Strand::Plus),
+                call(165, 336, 99.0, "S2C15H1L.2", Strand::Plus),
+            ],
+        )]);
+        assert!(resolve_overlaps(&mut monomers, None).is_empty());
+        assert_eq!(monomers["chr1"].len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_policies() {
+        let calls = vec![
+            call(0, 171, 99.0, "S2C15H1L.1", Strand::Plus),
+            call(171, 342, 95.0, "S2C15H1L.2", Strand::Plus),
+            call(171, 342, 97.0, "S2C15H3d.2", Strand::Minus),
+            call(342, 513, 99.0, "S2C15H1L.3", Strand::Plus),
+        ];
+        // Default prefers identity.
+        let mut monomers = HashMap::from([(String::from("chr1"), calls.clone())]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(
+            labels(&monomers["chr1"]),
+            [
+                "0-171:S2C15H1L.1(+)",
+                "171-342:S2C15H3d.2(-)",
+                "342-513:S2C15H1L.3(+)"
+            ]
+        );
+        assert_eq!(discarded[0].monomer.to_string(), "S2C15H1L.2");
+        assert_eq!(discarded[0].kept.3.to_string(), "S2C15H3d.2");
+
+        for policy in [OverlapPolicy::ConsistentStrand, OverlapPolicy::PreferLive] {
+            let mut monomers = HashMap::from([(String::from("chr1"), calls.clone())]);
+            let opts = OverlapOpts {
+                policies: vec![policy, OverlapPolicy::BestIdentity],
+                ..Default::default()
+            };
+            let discarded = resolve_overlaps(&mut monomers, Some(opts));
+            assert_eq!(
+                labels(&monomers["chr1"])[1],
+                "171-342:S2C15H1L.2(+)",
+                "{policy:?}"
+            );
+            assert_eq!(discarded[0].policy, Some(policy));
+        }
+    }
+
+    #[test]
+    fn test_resolve_multiple_overlaps() {
+        // 1 and 2 overlap too little to conflict but both conflict with 3.
+        let calls = |identity: f32| {
+            vec![
+                call(0, 171, 99.0, "S2C15H1L.1", Strand::Plus),
+                call(100, 271, 90.0, "S2C15H1L.2", Strand::Plus),
+                call(120, 200, identity, "S2C15H1L.3", Strand::Plus),
+            ]
+        };
+        let mut monomers = HashMap::from([(String::from("chr1"), calls(97.0))]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(
+            labels(&monomers["chr1"]),
+            ["0-171:S2C15H1L.1(+)", "100-271:S2C15H1L.2(+)"]
+        );
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(
+            (discarded[0].st, discarded[0].kept.3.to_string()),
+            (120, String::from("S2C15H1L.1"))
+        );
+
+        let mut monomers = HashMap::from([(String::from("chr1"), calls(100.0))]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(labels(&monomers["chr1"]), ["120-200:S2C15H1L.3(+)"]);
+        assert_eq!(
+            discarded.iter().map(|disc| disc.st).collect::<Vec<_>>(),
+            [100, 0]
+        );
+        assert!(discarded
+            .iter()
+            .all(|disc| disc.kept.3.to_string() == "S2C15H1L.3"));
+    }
+
+    #[test]
+    fn test_resolve_longest() {
+        let mut monomers = HashMap::from([(
+            String::from("chr1"),
+            vec![
+                call(0, 100, 99.0, "S2C15H1L.1", Strand::Plus),
+                call(0, 171, 99.0, "S2C15H1L.2", Strand::Plus),
+            ],
+        )]);
+        let opts = OverlapOpts {
+            policies: vec![OverlapPolicy::Longest],
+            ..Default::default()
+        };
+        resolve_overlaps(&mut monomers, Some(opts));
+        assert_eq!(labels(&monomers["chr1"]), ["0-171:S2C15H1L.2(+)"]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use eyre::{bail, Context, ContextCompat};
+use itertools::Itertools;
+
+use crate::{RepeatUnit, HOR};
+
+use super::io::StvRecord;
+
+/// A single element of a [`StvQuery`] pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum QueryTerm {
+    /// A monomer with exactly these numbers. ex. `5` or `6/4`
+    Exact(Vec<u8>),
+    /// Any one monomer. `?`
+    Any,
+    /// Zero or more monomers. `*`
+    Star,
+    /// One non-chimeric monomer with a number in any of the ranges. ex. `[3-5,7]`
+    Class(Vec<RangeInclusive<u8>>),
+}
+
+impl QueryTerm {
+    fn is_match(&self, numbers: &[u8]) -> bool {
+        match self {
+            QueryTerm::Exact(exp) => exp == numbers,
+            QueryTerm::Any => true,
+            QueryTerm::Star => unreachable!(),
+            QueryTerm::Class(ranges) => match numbers {
+                [num] => ranges.iter().any(|range| range.contains(num)),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// A contiguous pattern of [`QueryTerm`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QueryPattern {
+    terms: Vec<QueryTerm>,
+    anchor_start: bool,
+    anchor_end: bool,
+    negated: bool,
+}
+
+impl QueryPattern {
+    /// Check if pattern occurs in a sequence of monomer numbers.
+    fn is_match(&self, mons: &[&[u8]]) -> bool {
+        let (n_terms, n_mons) = (self.terms.len(), mons.len());
+        // matched[i][j]: terms[i..] match mons[j..] up to the end anchor.
+        let mut matched = vec![vec![false; n_mons + 1]; n_terms + 1];
+        for (j, row) in matched[n_terms].iter_mut().enumerate() {
+            *row = !self.anchor_end || j == n_mons;
+        }
+        for i in (0..n_terms).rev() {
+            for j in (0..=n_mons).rev() {
+                matched[i][j] = match &self.terms[i] {
+                    QueryTerm::Star => matched[i + 1][j] || (j < n_mons && matched[i][j + 1]),
+                    term => j < n_mons && term.is_match(mons[j]) && matched[i + 1][j + 1],
+                };
+            }
+        }
+        if self.anchor_start {
+            matched[0][0]
+        } else {
+            matched[0].iter().any(|m| *m)
+        }
+    }
+}
+
+fn parse_num(s: &str, query: &str) -> eyre::Result<u8> {
+    s.parse::<u8>()
+        .with_context(|| format!("Invalid monomer number ('{s}') in query, {query}."))
+}
+
+fn parse_pattern(pattern: &str, query: &str) -> eyre::Result<QueryPattern> {
+    let (negated, pattern) = pattern
+        .strip_prefix('!')
+        .map_or((false, pattern), |p| (true, p));
+    let (anchor_start, pattern) = pattern
+        .strip_prefix('^')
+        .map_or((false, pattern), |p| (true, p));
+    let (anchor_end, pattern) = pattern
+        .strip_suffix('$')
+        .map_or((false, pattern), |p| (true, p));
+    if pattern.is_empty() {
+        bail!("Empty pattern in query, {query}.")
+    }
+
+    let mut terms = vec![];
+    for term in pattern.split('_') {
+        match term {
+            "?" => terms.push(QueryTerm::Any),
+            "*" => terms.push(QueryTerm::Star),
+            _ if term.starts_with('[') => {
+                let ranges = term
+                    .strip_prefix('[')
+                    .and_then(|t| t.strip_suffix(']'))
+                    .with_context(|| format!("Unclosed class ('{term}') in query, {query}."))?
+                    .split(',')
+                    .map(|range| {
+                        if let Some((st, end)) = range.split_once('-') {
+                            let (st, end) = (parse_num(st, query)?, parse_num(end, query)?);
+                            Ok(st.min(end)..=st.max(end))
+                        } else {
+                            let num = parse_num(range, query)?;
+                            Ok(num..=num)
+                        }
+                    })
+                    .collect::<eyre::Result<Vec<RangeInclusive<u8>>>>()?;
+                terms.push(QueryTerm::Class(ranges));
+            }
+            _ if term.contains('/') => {
+                let nums = term
+                    .split('/')
+                    .map(|num| parse_num(num, query))
+                    .collect::<eyre::Result<Vec<u8>>>()?;
+                terms.push(QueryTerm::Exact(nums));
+            }
+            _ => match term.split_once('-') {
+                Some((st, end)) => {
+                    let (st, end) = (parse_num(st, query)?, parse_num(end, query)?);
+                    if st <= end {
+                        terms.extend((st..=end).map(|num| QueryTerm::Exact(vec![num])));
+                    } else {
+                        terms.extend((end..=st).rev().map(|num| QueryTerm::Exact(vec![num])));
+                    }
+                }
+                None => terms.push(QueryTerm::Exact(vec![parse_num(term, query)?])),
+            },
+        }
+    }
+    Ok(QueryPattern {
+        terms,
+        anchor_start,
+        anchor_end,
+        negated,
+    })
+}
+
+/// A compiled query over the monomer order of StVs.
+///
+/// A query is one or more whitespace-separated patterns that must all match.
+/// Each pattern is a sequence of terms delimited by `_` that must occur contiguously in the StV.
+/// * `5`: Monomer 5.
+/// * `3-5`: Monomers 3, 4 and 5 in order. Descending if `5-3`.
+/// * `6/4`: Chimeric monomer 6/4.
+/// * `?`: Any one monomer.
+/// * `*`: Zero or more monomers.
+/// * `[3-5,7]`: One monomer numbered 3 to 5 or 7.
+///
+/// Patterns can be modified with:
+/// * `^` and `$`: Anchor the pattern to the start or end of the StV.
+/// * `!`: Assert the pattern is absent.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{StvQuery, HOR};
+///
+/// // Monomers 3-5 deleted.
+/// let query = StvQuery::new("![3-5]").unwrap();
+/// assert!(query.is_match(&HOR::new("S1C1/5/19H1L.1-2_6").unwrap()));
+/// assert!(!query.is_match(&HOR::new("S1C1/5/19H1L.1-6").unwrap()));
+///
+/// // Chimera 6/4 followed by 5.
+/// let query = StvQuery::new("6/4_5").unwrap();
+/// assert!(query.is_match(&HOR::new("S1C1/5/19H1L.1-5_6/4_5-6").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StvQuery {
+    patterns: Vec<QueryPattern>,
+}
+
+impl StvQuery {
+    /// Compile a new [`StvQuery`] from a query string.
+    pub fn new(query: &str) -> eyre::Result<Self> {
+        StvQuery::from_str(query)
+    }
+
+    /// Check if a [`HOR`] matches this query.
+    pub fn is_match<M: RepeatUnit>(&self, hor: &HOR<M>) -> bool {
+        let mons = hor.monomers().iter().map(|mon| mon.numbers()).collect_vec();
+        self.patterns
+            .iter()
+            .all(|pattern| pattern.is_match(&mons) != pattern.negated)
+    }
+
+    /// Filter [`StvRecord`]s to those matching this query.
+    ///
+    /// ```
+    /// use rs_asat_hor::{read_from_monomer_bed, StvQuery};
+    ///
+    /// let records = read_from_monomer_bed("test/mons.bed", |_| false).unwrap();
+    /// let query = StvQuery::new("^11-1$").unwrap();
+    /// assert_eq!(query.filter(&records).count(), 2);
+    /// ```
+    pub fn filter<'a>(
+        &'a self,
+        records: impl IntoIterator<Item = &'a StvRecord> + 'a,
+    ) -> impl Iterator<Item = &'a StvRecord> + 'a {
+        records.into_iter().filter(|rec| self.is_match(&rec.hor))
+    }
+}
+
+impl FromStr for StvQuery {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let patterns = s
+            .split_whitespace()
+            .map(|pattern| parse_pattern(pattern, s))
+            .collect::<eyre::Result<Vec<QueryPattern>>>()?;
+        if patterns.is_empty() {
+            bail!("Empty query.")
+        }
+        Ok(Self { patterns })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{StvQuery, HOR};
+
+    fn matches(query: &str, hor: &str) -> bool {
+        StvQuery::new(query)
+            .unwrap()
+            .is_match(&HOR::new(hor).unwrap())
+    }
+
+    #[test]
+    fn test_query_exact() {
+        assert!(matches("3-5", "S1C1/5/19H1L.1-6"));
+        assert!(matches("5-3", "S1C1/5/19H1L.6-1"));
+        assert!(!matches("5-3", "S1C1/5/19H1L.1-6"));
+        assert!(matches("2_6", "S1C1/5/19H1L.1-2_6"));
+        assert!(!matches("2_6", "S1C1/5/19H1L.1-6"));
+    }
+
+    #[test]
+    fn test_query_anchors() {
+        assert!(matches("^1-6$", "S1C1/5/19H1L.1-6"));
+        assert!(!matches("^1-5$", "S1C1/5/19H1L.1-6"));
+        assert!(matches("^1-2", "S1C1/5/19H1L.1-6"));
+        assert!(!matches("^2", "S1C1/5/19H1L.1-6"));
+        assert!(matches("6$", "S1C1/5/19H1L.1-6"));
+    }
+
+    #[test]
+    fn test_query_wildcards() {
+        assert!(matches("2_?_4", "S1C1/5/19H1L.1-6"));
+        assert!(matches("2_?_4", "S1C1/5/19H1L.1-2_6/4_4"));
+        assert!(!matches("2_?_4", "S1C1/5/19H1L.1-2_4"));
+        assert!(matches("^1_*_6$", "S1C1/5/19H1L.1_6"));
+        assert!(matches("^1_*_6$", "S1C1/5/19H1L.1_3/4_6"));
+        assert!(matches("[3-5,7]_[7]", "S1C1/5/19H1L.1_5_7"));
+        // Classes don't match chimeras.
+        assert!(!matches("[3-5]", "S1C1/5/19H1L.1_3/4_6"));
+    }
+
+    #[test]
+    fn test_query_chimera() {
+        assert!(matches("6/4_5", "S1C1/5/19H1L.1-5_6/4_5-6"));
+        assert!(!matches("4/6_5", "S1C1/5/19H1L.1-5_6/4_5-6"));
+        assert!(!matches("6/4", "S1C1/5/19H1L.1-6"));
+    }
+
+    #[test]
+    fn test_query_absence() {
+        assert!(matches("1 ![3-5]", "S1C1/5/19H1L.1-2_6"));
+        assert!(!matches("1 ![3-5]", "S1C1/5/19H1L.1-2_4_6"));
+        assert!(matches("!3-5", "S1C1/5/19H1L.1-4_6"));
+        assert!(matches("!6/4", "S1C1/5/19H1L.1-6"));
+    }
+
+    #[test]
+    fn test_query_invalid() {
+        for query in ["", "1_a", "[3-5", "1/", "!", "^$", "1-300"] {
+            assert!(StvQuery::new(query).is_err(), "{query}");
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt::Display, str::FromStr};
+
+use eyre::ContextCompat;
+
+/// A region of a parent contig used as a contig name.
+/// * Formatted as `name:start-end`.
+/// * `start` is 0-based and `end` is exclusive, as in `BED`.
+///
+/// ```
+/// use rs_asat_hor::Region;
+///
+/// let region: Region = "chm1_chr15:3977696-8919402".parse().unwrap();
+/// assert_eq!(region.name, "chm1_chr15");
+/// assert_eq!(region.start, 3977696);
+/// assert_eq!(region.end, 8919402);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Region {
+    /// Length of this [`Region`].
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Check if this [`Region`] has no length.
+    pub fn is_empty(&self) -> bool {
+        self.end == self.start
+    }
+
+    /// Shift a coordinate relative to this [`Region`] into the parent contig's frame.
+    ///
+    /// ```
+    /// use rs_asat_hor::Region;
+    ///
+    /// let region = Region::new("chm1_chr15:3977696-8919402").unwrap();
+    /// assert_eq!(region.to_parent(2732060), 6709756);
+    /// ```
+    pub fn to_parent(&self, pos: u64) -> u64 {
+        self.start + pos
+    }
+
+    /// Shift a coordinate in the parent contig's frame to be relative to this [`Region`].
+    /// * Saturates at `0` if the coordinate is before this [`Region`].
+    ///
+    /// ```
+    /// use rs_asat_hor::Region;
+    ///
+    /// let region = Region::new("chm1_chr15:3977696-8919402").unwrap();
+    /// assert_eq!(region.to_relative(6709756), 2732060);
+    /// ```
+    pub fn to_relative(&self, pos: u64) -> u64 {
+        pos.saturating_sub(self.start)
+    }
+
+    /// Construct a new [`Region`] from a given string.
+    pub fn new(s: &str) -> eyre::Result<Self> {
+        Region::from_str(s)
+    }
+}
+
+/// Split a contig name formatted as `name:start-end` into its parts.
+/// * Splits on the last `:` as contig names may contain `:`.
+/// * Regions must have a length. ex. `chr1:10-10` is not a region.
+pub(crate) fn split_region(s: &str) -> Option<(&str, u64, u64)> {
+    let (name, coords) = s.rsplit_once(':')?;
+    let (start, end) = coords.split_once('-')?;
+    let (start, end) = (start.parse::<u64>().ok()?, end.parse::<u64>().ok()?);
+    (!name.is_empty() && start < end).then_some((name, start, end))
+}
+
+impl FromStr for Region {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, start, end) = split_region(s).with_context(|| {
+            format!("Invalid region, {s}. Expected name:start-end with start < end.")
+        })?;
+        Ok(Region {
+            name: name.to_owned(),
+            start,
+            end,
+        })
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}-{}", self.name, self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{split_region, Region};
+
+    #[test]
+    fn test_region() {
+        const REGION: &str = "chm1_chr15:3977696-8919402";
+        let region = Region::new(REGION).unwrap();
+        assert_eq!(
+            region,
+            Region {
+                name: String::from("chm1_chr15"),
+                start: 3977696,
+                end: 8919402
+            }
+        );
+        assert_eq!(format!("{region}"), REGION);
+    }
+
+    #[test]
+    fn test_region_colon_in_name() {
+        let region = Region::new("HG002#1#chr15:10-20").unwrap();
+        assert_eq!(region.name, "HG002#1#chr15");
+
+        let region = Region::new("sample:chr15:10-20").unwrap();
+        assert_eq!(region.name, "sample:chr15");
+    }
+
+    #[test]
+    fn test_invalid_region() {
+        assert!(Region::new("chm1_chr15").is_err());
+        assert!(Region::new("chm1_chr15:10").is_err());
+        assert!(Region::new("chm1_chr15:20-10").is_err());
+        assert!(Region::new("chm1_chr15:10-10").is_err());
+        assert!(Region::new(":10-20").is_err());
+        assert!(Region::new("chm1_chr15:a-20").is_err());
+    }
+
+    #[test]
+    fn test_split_region_matches_region() {
+        for s in [
+            "chm1_chr15:10-20",
+            "chm1_chr15:10-10",
+            "chm1_chr15:20-10",
+            ":10-20",
+        ] {
+            assert_eq!(split_region(s).is_some(), Region::new(s).is_ok(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use eyre::bail;
+use itertools::Itertools;
+
+use crate::{Monomer, RepeatUnit, Strand};
+
+use super::{io::ContigMonomers, mon_to_hor::is_hor_break, overlap::DiscardedMonomer};
+
+/// A candidate label and its score.
+type Candidate = (Monomer, f32);
+
+/// Options for [`relabel_monomers`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelabelOpts {
+    /// Score subtracted for each break in HOR continuity.
+    /// * Scores are usually percent identity so a relabel is made if it removes a break and loses less identity.
+    pub break_penalty: f32,
+}
+
+impl Default for RelabelOpts {
+    fn default() -> Self {
+        Self {
+            break_penalty: 10.0,
+        }
+    }
+}
+
+/// A monomer relabelled by [`relabel_monomers`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelabelledMonomer<M = Monomer> {
+    /// Position of the monomer in the input.
+    pub idx: usize,
+    pub original: M,
+    pub relabelled: M,
+    /// Change in score from the original label. Usually negative.
+    pub score_change: f32,
+    /// Number of HOR breaks with adjacent monomers with the original labels.
+    pub breaks_before: usize,
+    /// Number of HOR breaks with adjacent monomers after relabelling.
+    pub breaks_after: usize,
+}
+
+/// Check if the HOR breaks between two consecutive units with the same rules as [`crate::monomers_to_hor`].
+fn is_break<M: RepeatUnit>(mon_1: &M, mon_2: &M) -> bool {
+    if mon_1.repeat_name() != mon_2.repeat_name() {
+        return true;
+    }
+    let (Some(mon_1_num), Some(mon_2_num)) = (mon_1.right_most_num(), mon_2.left_most_num()) else {
+        return true;
+    };
+    is_hor_break(
+        *mon_1_num,
+        *mon_2_num,
+        mon_2.strand().unwrap_or(Strand::Plus),
+    )
+}
+
+/// Choose the labelling of monomers that maximizes HOR continuity with a Viterbi pass.
+/// * The labelling maximizes the sum of candidate scores minus [`RelabelOpts::break_penalty`] for each break.
+/// * Breaks follow the ordering rules of [`crate::monomers_to_hor`].
+/// * Ties are broken towards the first candidate of each position.
+///
+/// # Args
+/// * `candidates`
+///     * Candidate labels and their scores for each position in order. ex. The top HMMER hits and their identity.
+///     * The first candidate of each position is the original label.
+/// * `opts`
+///     * [`RelabelOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * Chosen label of each position and the [`RelabelledMonomer`]s that differ from the original label.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{monomers_to_hor, relabel_monomers, Monomer};
+///
+/// let mon = |name: &str| Monomer::new(name).unwrap();
+/// let candidates = vec![
+///     vec![(mon("S1C1/5/19H1L.1"), 99.0)],
+///     vec![(mon("S1C1/5/19H1L.2"), 99.0)],
+///     // Low identity monomer mislabelled as 5.
+///     vec![(mon("S1C1/5/19H1L.5"), 80.0), (mon("S1C1/5/19H1L.3"), 78.0)],
+///     vec![(mon("S1C1/5/19H1L.4"), 99.0)],
+/// ];
+/// let (monomers, relabelled) = relabel_monomers(&candidates, None).unwrap();
+/// let hors = monomers_to_hor(monomers.iter(), None).unwrap();
+/// assert_eq!(hors[0].to_string(), "S1C1/5/19H1L.1-4");
+/// assert_eq!((relabelled[0].idx, relabelled[0].breaks_before, relabelled[0].breaks_after), (2, 2, 0));
+/// ```
+pub fn relabel_monomers<M: RepeatUnit>(
+    candidates: &[Vec<(M, f32)>],
+    opts: Option<RelabelOpts>,
+) -> eyre::Result<(Vec<M>, Vec<RelabelledMonomer<M>>)> {
+    let opts = opts.unwrap_or_default();
+    if let Some(idx) = candidates.iter().position(|cands| cands.is_empty()) {
+        bail!("No candidate labels at position {idx}.")
+    }
+
+    // scores[i][j]: Best score of a labelling ending with candidate j at position i.
+    let mut scores: Vec<Vec<f32>> = Vec::with_capacity(candidates.len());
+    let mut traceback: Vec<Vec<usize>> = Vec::with_capacity(candidates.len());
+    for (i, cands) in candidates.iter().enumerate() {
+        let Some(prev_cands) = i.checked_sub(1).map(|prev| &candidates[prev]) else {
+            scores.push(cands.iter().map(|(_, score)| *score).collect());
+            traceback.push(vec![0; cands.len()]);
+            continue;
+        };
+        let prev_scores = &scores[i - 1];
+        let (row_scores, row_traceback): (Vec<f32>, Vec<usize>) = cands
+            .iter()
+            .map(|(mon, score)| {
+                let (best_prev, best_score) = prev_cands
+                    .iter()
+                    .zip(prev_scores)
+                    .map(|((prev_mon, _), prev_score)| {
+                        let penalty = if is_break(prev_mon, mon) {
+                            opts.break_penalty
+                        } else {
+                            0.0
+                        };
+                        prev_score - penalty
+                    })
+                    .enumerate()
+                    // Keep the first of equal scores.
+                    .fold((0, f32::NEG_INFINITY), |best, (j, s)| {
+                        if s > best.1 {
+                            (j, s)
+                        } else {
+                            best
+                        }
+                    });
+                (best_score + score, best_prev)
+            })
+            .unzip();
+        scores.push(row_scores);
+        traceback.push(row_traceback);
+    }
+
+    // Trace back the best labelling.
+    let mut chosen = vec![0; candidates.len()];
+    if let Some(last) = scores.last() {
+        let mut j = last
+            .iter()
+            .enumerate()
+            .fold((0, f32::NEG_INFINITY), |best, (j, s)| {
+                if *s > best.1 {
+                    (j, *s)
+                } else {
+                    best
+                }
+            })
+            .0;
+        for i in (0..candidates.len()).rev() {
+            chosen[i] = j;
+            j = traceback[i][j];
+        }
+    }
+
+    let monomers = candidates
+        .iter()
+        .zip(&chosen)
+        .map(|(cands, j)| cands[*j].0.clone())
+        .collect_vec();
+    let fn_n_breaks = |mons: &[&M], i: usize| {
+        let mut n_breaks = 0;
+        if i > 0 && is_break(mons[i - 1], mons[i]) {
+            n_breaks += 1;
+        }
+        if i + 1 < mons.len() && is_break(mons[i], mons[i + 1]) {
+            n_breaks += 1;
+        }
+        n_breaks
+    };
+    let original = candidates.iter().map(|cands| &cands[0].0).collect_vec();
+    let new = monomers.iter().collect_vec();
+    let relabelled = chosen
+        .iter()
+        .enumerate()
+        .filter(|(_, j)| **j != 0)
+        .map(|(i, j)| RelabelledMonomer {
+            idx: i,
+            original: candidates[i][0].0.clone(),
+            relabelled: candidates[i][*j].0.clone(),
+            score_change: candidates[i][*j].1 - candidates[i][0].1,
+            breaks_before: fn_n_breaks(&original, i),
+            breaks_after: fn_n_breaks(&new, i),
+        })
+        .collect();
+    Ok((monomers, relabelled))
+}
+
+/// Relabel monomers by contig using overlapping calls discarded by [`crate::resolve_overlaps`] as candidates.
+/// * Candidates of each kept monomer are itself and the calls discarded in its favor, scored by identity.
+///     * Includes calls discarded in favor of a call that was later replaced by it.
+/// * Monomers are relabelled in place, keeping their coordinates. Identity is set to that of the new label.
+///
+/// # Args
+/// * `monomers`
+///     * Resolved [`ContigMonomers`].
+/// * `discarded`
+///     * [`DiscardedMonomer`]s from resolving `monomers`.
+/// * `opts`
+///     * [`RelabelOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`RelabelledMonomer`]s with their contig.
+pub fn relabel_contig_monomers<'a>(
+    monomers: &mut ContigMonomers,
+    discarded: &'a [DiscardedMonomer],
+    opts: Option<RelabelOpts>,
+) -> eyre::Result<Vec<(String, RelabelledMonomer)>> {
+    // Calls as (chrom, st, end, monomer).
+    let fn_kept_key = |disc: &'a DiscardedMonomer| {
+        let (st, end, _, kept) = &disc.kept;
+        (disc.chrom.as_str(), *st, *end, kept.to_string())
+    };
+    // A kept call can itself be discarded by a later call.
+    let replaced_by: HashMap<_, _> = discarded
+        .iter()
+        .map(|disc| {
+            let key = (
+                disc.chrom.as_str(),
+                disc.st,
+                disc.end,
+                disc.monomer.to_string(),
+            );
+            (key, fn_kept_key(disc))
+        })
+        .collect();
+    // Keyed by the final kept call.
+    let mut alternatives: HashMap<(&str, u64, u64, String), Vec<Candidate>> = HashMap::new();
+    for disc in discarded {
+        let mut key = fn_kept_key(disc);
+        // Bounded in case identical calls replace each other.
+        for _ in 0..discarded.len() {
+            match replaced_by.get(&key) {
+                Some(next) if *next != key => key = next.clone(),
+                _ => break,
+            }
+        }
+        alternatives
+            .entry(key)
+            .or_default()
+            .push((disc.monomer.clone(), disc.identity));
+    }
+
+    let mut all_relabelled = vec![];
+    for (chrom, mons) in monomers.iter_mut().sorted_by(|a, b| a.0.cmp(b.0)) {
+        let candidates = mons
+            .iter()
+            .map(|(st, end, identity, mon)| {
+                let mut cands = vec![(mon.clone(), *identity)];
+                if let Some(alts) = alternatives.get(&(chrom.as_str(), *st, *end, mon.to_string()))
+                {
+                    cands.extend(alts.iter().cloned());
+                }
+                cands
+            })
+            .collect_vec();
+        let (_, relabelled) = relabel_monomers(&candidates, opts)?;
+        for rel in relabelled {
+            mons[rel.idx].2 += rel.score_change;
+            mons[rel.idx].3 = rel.relabelled.clone();
+            all_relabelled.push((chrom.clone(), rel));
+        }
+    }
+    Ok(all_relabelled)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        monomers_to_hor, relabel_contig_monomers, relabel_monomers, resolve_overlaps, Monomer,
+        RelabelOpts, Strand,
+    };
+
+    fn mon(name: &str, strand: Strand) -> Monomer {
+        Monomer::new(name).unwrap().with_strand(strand)
+    }
+
+    #[test]
+    fn test_relabel_minus_strand() {
+        let candidates = [6, 5, 1, 3, 2]
+            .into_iter()
+            .map(|num| {
+                let mut cands = vec![(mon(&format!("S1C1/5/19H1L.{num}"), Strand::Minus), 90.0)];
+                if num == 1 {
+                    cands.push((mon("S1C1/5/19H1L.4", Strand::Minus), 85.0));
+                }
+                cands
+            })
+            .collect::<Vec<_>>();
+        let (mons, relabelled) = relabel_monomers(&candidates, None).unwrap();
+        let hors = monomers_to_hor(mons.iter(), None).unwrap();
+        assert_eq!(hors.len(), 1);
+        assert_eq!(hors[0].to_string(), "S1C1/5/19H1L.6-2");
+        assert_eq!(relabelled.len(), 1);
+        assert_eq!(relabelled[0].score_change, -5.0);
+    }
+
+    #[test]
+    fn test_relabel_penalty() {
+        let candidates = vec![
+            vec![(mon("S1C1/5/19H1L.1", Strand::Plus), 99.0)],
+            vec![
+                (mon("S1C1/5/19H1L.5", Strand::Plus), 99.0),
+                (mon("S1C1/5/19H1L.2", Strand::Plus), 70.0),
+            ],
+            vec![(mon("S1C1/5/19H1L.3", Strand::Plus), 99.0)],
+        ];
+        // Losing 29% identity costs more than two breaks.
+        let (_, relabelled) = relabel_monomers(&candidates, None).unwrap();
+        assert!(relabelled.is_empty());
+        let opts = RelabelOpts {
+            break_penalty: 20.0,
+        };
+        let (_, relabelled) = relabel_monomers(&candidates, Some(opts)).unwrap();
+        assert_eq!(relabelled.len(), 1);
+
+        assert!(relabel_monomers::<Monomer>(&[vec![]], None).is_err());
+        assert!(relabel_monomers::<Monomer>(&[], None).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn test_relabel_contig_monomers() {
+        let mut monomers = HashMap::from([(
+            String::from("chr1"),
+            vec![
+                (0, 171, 99.0, mon("S1C1/5/19H1L.1", Strand::Plus)),
+                (171, 342, 80.0, mon("S1C1/5/19H1L.6", Strand::Plus)),
+                (171, 342, 79.0, mon("S1C1/5/19H1L.2", Strand::Plus)),
+                (342, 513, 99.0, mon("S1C1/5/19H1L.3", Strand::Plus)),
+            ],
+        )]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(discarded.len(), 1);
+        let relabelled = relabel_contig_monomers(&mut monomers, &discarded, None).unwrap();
+        assert_eq!(relabelled.len(), 1);
+        assert_eq!(relabelled[0].0, "chr1");
+        assert_eq!(monomers["chr1"][1].3.to_string(), "S1C1/5/19H1L.2");
+        assert_eq!(monomers["chr1"][1].2, 79.0);
+    }
+
+    #[test]
+    fn test_relabel_replaced_kept_call() {
+        let mut monomers = HashMap::from([(
+            String::from("chr1"),
+            vec![
+                (0, 171, 99.0, mon("S1C1/5/19H1L.1", Strand::Plus)),
+                // Winner changes twice. 2 is discarded for 6, then 6 for 4.
+                (171, 342, 90.0, mon("S1C1/5/19H1L.2", Strand::Plus)),
+                (171, 342, 95.0, mon("S1C1/5/19H1L.6", Strand::Plus)),
+                (171, 342, 97.0, mon("S1C1/5/19H1L.4", Strand::Plus)),
+                (342, 513, 99.0, mon("S1C1/5/19H1L.3", Strand::Plus)),
+            ],
+        )]);
+        let discarded = resolve_overlaps(&mut monomers, None);
+        assert_eq!(
+            discarded
+                .iter()
+                .map(|disc| (disc.monomer.to_string(), disc.kept.3.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    String::from("S1C1/5/19H1L.2"),
+                    String::from("S1C1/5/19H1L.6")
+                ),
+                (
+                    String::from("S1C1/5/19H1L.6"),
+                    String::from("S1C1/5/19H1L.4")
+                ),
+            ]
+        );
+        let relabelled = relabel_contig_monomers(&mut monomers, &discarded, None).unwrap();
+        assert_eq!(relabelled.len(), 1);
+        assert_eq!(monomers["chr1"][1].3.to_string(), "S1C1/5/19H1L.2");
+        assert_eq!(monomers["chr1"][1].2, 90.0);
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashMap, fmt::Display};
+
+use itertools::Itertools;
+
+use super::validate::BedIssueKind;
+
+/// Reason a line was skipped when reading [`crate::MonomerRecord`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Line could not be parsed.
+    Invalid(BedIssueKind),
+    /// Record was rejected by the filter function.
+    Filtered,
+}
+
+impl SkipReason {
+    /// Short label of the reason, used to count reasons in [`ReadReport::counts`].
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkipReason::Invalid(BedIssueKind::WrongColumnCount(_)) => "wrong_column_count",
+            SkipReason::Invalid(BedIssueKind::InvalidNumber(_, _)) => "invalid_number",
+            SkipReason::Invalid(BedIssueKind::InvalidStrand(_)) => "invalid_strand",
+            SkipReason::Invalid(BedIssueKind::UnparsableName(_)) => "unparsable_name",
+            SkipReason::Invalid(_) => "invalid",
+            SkipReason::Filtered => "filtered",
+        }
+    }
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Invalid(kind) => write!(f, "{kind}"),
+            SkipReason::Filtered => write!(f, "Rejected by filter."),
+        }
+    }
+}
+
+/// A line skipped when reading [`crate::MonomerRecord`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRecord {
+    /// `1`-based line number.
+    pub line: usize,
+    pub chrom: String,
+    /// Start and end of the record, if they could be parsed.
+    pub span: Option<(u64, u64)>,
+    pub reason: SkipReason,
+}
+
+impl Display for SkippedRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {} ({}", self.line, self.chrom)?;
+        if let Some((st, end)) = self.span {
+            write!(f, ":{st}-{end}")?;
+        }
+        write!(f, "): {}", self.reason)
+    }
+}
+
+/// Report of records read and skipped by [`crate::read_monomer_records_with_report`].
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{read_monomer_records_with_report, MonomerRecord};
+///
+/// let (records, report) =
+///     read_monomer_records_with_report("test/mons.bed", |rec: &MonomerRecord| rec.identity() < 99.0)
+///         .unwrap();
+/// assert_eq!(report.n_read, records.len());
+/// assert_eq!(report.counts()["filtered"], 5);
+/// // Stop if more than half of a contig was dropped.
+/// assert!(report.fraction_skipped_bp("chm1_chr15:3977696-8919402") < 0.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadReport {
+    /// Number of records read.
+    pub n_read: usize,
+    /// Skipped lines in file order.
+    pub skipped: Vec<SkippedRecord>,
+    /// Total length in bp of read records by contig.
+    pub read_bp: HashMap<String, u64>,
+}
+
+impl ReadReport {
+    /// Count skipped lines by [`SkipReason::label`].
+    pub fn counts(&self) -> HashMap<&'static str, usize> {
+        self.skipped.iter().map(|rec| rec.reason.label()).counts()
+    }
+
+    /// Check if any line failed to parse. Filtered records are not failures.
+    pub fn has_failures(&self) -> bool {
+        self.skipped
+            .iter()
+            .any(|rec| rec.reason != SkipReason::Filtered)
+    }
+
+    /// Get the merged genomic spans of skipped records by contig, sorted by position.
+    pub fn skipped_spans(&self) -> HashMap<String, Vec<(u64, u64)>> {
+        let mut spans: HashMap<String, Vec<(u64, u64)>> = HashMap::new();
+        for rec in &self.skipped {
+            if let Some(span) = rec.span {
+                spans.entry(rec.chrom.clone()).or_default().push(span);
+            }
+        }
+        for contig_spans in spans.values_mut() {
+            contig_spans.sort();
+            *contig_spans = contig_spans
+                .drain(..)
+                .coalesce(|(st_1, end_1), (st_2, end_2)| {
+                    if st_2 <= end_1 {
+                        Ok((st_1, end_1.max(end_2)))
+                    } else {
+                        Err(((st_1, end_1), (st_2, end_2)))
+                    }
+                })
+                .collect();
+        }
+        spans
+    }
+
+    /// Fraction of bp of a contig's records that were skipped, from `0.0` to `1.0`.
+    /// * `0.0` if the contig has no records.
+    pub fn fraction_skipped_bp(&self, chrom: &str) -> f32 {
+        let skipped_bp: u64 = self
+            .skipped_spans()
+            .get(chrom)
+            .map(|spans| spans.iter().map(|(st, end)| end.saturating_sub(*st)).sum())
+            .unwrap_or_default();
+        let total_bp = skipped_bp + self.read_bp.get(chrom).copied().unwrap_or_default();
+        if total_bp == 0 {
+            return 0.0;
+        }
+        skipped_bp as f32 / total_bp as f32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ReadReport, SkipReason, SkippedRecord};
+    use crate::BedIssueKind;
+
+    #[test]
+    fn test_report_spans() {
+        let report = ReadReport {
+            n_read: 1,
+            skipped: vec![
+                SkippedRecord {
+                    line: 1,
+                    chrom: "chr1".to_owned(),
+                    span: Some((0, 171)),
+                    reason: SkipReason::Filtered,
+                },
+                SkippedRecord {
+                    line: 2,
+                    chrom: "chr1".to_owned(),
+                    span: Some((100, 342)),
+                    reason: SkipReason::Invalid(BedIssueKind::UnparsableName("x".to_owned())),
+                },
+                SkippedRecord {
+                    line: 3,
+                    chrom: "chr1".to_owned(),
+                    span: None,
+                    reason: SkipReason::Invalid(BedIssueKind::WrongColumnCount(3)),
+                },
+            ],
+            read_bp: [("chr1".to_owned(), 342)].into_iter().collect(),
+        };
+        assert_eq!(report.skipped_spans()["chr1"], [(0, 342)]);
+        assert_eq!(report.fraction_skipped_bp("chr1"), 0.5);
+        assert_eq!(report.fraction_skipped_bp("chr2"), 0.0);
+        assert_eq!(report.counts()["filtered"], 1);
+        assert!(report.has_failures());
+        assert_eq!(
+            report.skipped[1].to_string(),
+            "Line 2 (chr1:100-342): Cannot parse monomer, x."
+        );
+    }
+}
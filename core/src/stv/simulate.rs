@@ -0,0 +1,387 @@
+use std::{collections::HashMap, io::Write};
+
+use eyre::bail;
+use itertools::Itertools;
+
+use crate::{utils::reverse_complement, Monomer, MonomerLibrary, RepeatUnit, Strand, HOR};
+
+use super::{
+    fasta::write_fasta_record,
+    io::{write_to_monomer_bed, write_to_stv_bed, ContigMonomers, CoordFrame, StvRecord},
+};
+
+const BASES: &[u8; 4] = b"ACGT";
+
+/// Options for [`simulate_array`].
+/// * Rates are probabilities from `0.0` to `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationOpts {
+    /// Seed of the random number generator. The same seed and inputs give the same array.
+    pub seed: u64,
+    /// Probability of a point mutation at each base.
+    pub mutation_rate: f32,
+    /// Probability of fusing two adjacent monomers of an StV copy into a chimeric monomer.
+    pub chimera_rate: f32,
+    /// Probability of an StV copy being inverted onto the minus strand.
+    pub inversion_rate: f32,
+    /// Probability of inserting non-satellite sequence after an StV copy.
+    pub insertion_rate: f32,
+    /// Length in bp of inserted non-satellite sequence.
+    pub insertion_length: usize,
+}
+
+impl Default for SimulationOpts {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            mutation_rate: 0.01,
+            chimera_rate: 0.0,
+            inversion_rate: 0.0,
+            insertion_rate: 0.0,
+            insertion_length: 500,
+        }
+    }
+}
+
+/// A xorshift64* random number generator. Not for cryptographic use.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // State must be non-zero.
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Random index in `0..n`.
+    fn index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns `true` with probability `p`.
+    fn chance(&mut self, p: f32) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p as f64
+    }
+}
+
+/// A simulated HOR array with its ground truth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedArray {
+    pub chrom: String,
+    pub sequence: Vec<u8>,
+    /// Truth monomers as `(st, end, identity, monomer)` in order.
+    /// * Identity is the percent of bases without point mutations.
+    pub monomers: Vec<(u64, u64, f32, Monomer)>,
+    /// Truth StVs with one record per simulated copy.
+    pub records: Vec<StvRecord>,
+}
+
+impl SimulatedArray {
+    /// Truth monomers grouped by contig. See [`ContigMonomers`].
+    pub fn contig_monomers(&self) -> ContigMonomers {
+        HashMap::from_iter([(self.chrom.clone(), self.monomers.clone())])
+    }
+
+    /// Write the array sequence as `FASTA`.
+    pub fn write_fasta(&self, mut output: impl Write) -> eyre::Result<()> {
+        write_fasta_record(&mut output, &self.chrom, &self.sequence)
+    }
+
+    /// Write the truth monomers as a `BED9` file. See [`write_to_monomer_bed`].
+    pub fn write_monomer_bed(&self, output: impl Write) -> eyre::Result<()> {
+        write_to_monomer_bed(output, &self.contig_monomers())
+    }
+
+    /// Write the truth StVs as a `BED4` file. See [`write_to_stv_bed`].
+    pub fn write_stv_bed(&self, output: impl Write) -> eyre::Result<()> {
+        write_to_stv_bed(output, &self.records, CoordFrame::Contig)
+    }
+}
+
+/// Simulate a HOR array from StVs and monomer consensus sequences.
+/// * StV copies are shuffled and placed end to end.
+/// * Each copy can gain a chimeric monomer, be inverted, or be followed by inserted non-satellite sequence.
+/// * Point mutations are applied to monomer sequences after all other events.
+///
+/// # Args
+/// * `chrom`
+///     * Name of the simulated contig.
+/// * `composition`
+///     * StVs and their number of copies. ex. The canonical HOR and its common variants.
+/// * `library`
+///     * [`MonomerLibrary`] containing every monomer in `composition`.
+/// * `opts`
+///     * [`SimulationOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`SimulatedArray`] with its sequence, truth monomers, and truth StVs.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::{simulate_array, MonomerLibrary, SimulationOpts, HOR};
+///
+/// let fa = b">S1C1/5/19H1L.1\nAAAAAAAA\n>S1C1/5/19H1L.2\nCCCCCCCC\n>S1C1/5/19H1L.3\nGGGGGGGG\n";
+/// let library = MonomerLibrary::from_reader(&fa[..]).unwrap();
+/// let composition = [
+///     (HOR::new("S1C1/5/19H1L.1-3").unwrap(), 3),
+///     (HOR::new("S1C1/5/19H1L.1_3").unwrap(), 1),
+/// ];
+/// let opts = SimulationOpts { mutation_rate: 0.0, ..Default::default() };
+/// let array = simulate_array("sim", &composition, &library, Some(opts)).unwrap();
+/// assert_eq!(array.sequence.len(), 88);
+/// assert_eq!(array.records.len(), 4);
+/// assert_eq!(array.monomers.len(), 11);
+/// ```
+pub fn simulate_array(
+    chrom: &str,
+    composition: &[(HOR, usize)],
+    library: &MonomerLibrary,
+    opts: Option<SimulationOpts>,
+) -> eyre::Result<SimulatedArray> {
+    let opts = opts.unwrap_or_default();
+    for (name, rate) in [
+        ("mutation", opts.mutation_rate),
+        ("chimera", opts.chimera_rate),
+        ("inversion", opts.inversion_rate),
+        ("insertion", opts.insertion_rate),
+    ] {
+        if !(0.0..=1.0).contains(&rate) {
+            bail!("Invalid {name} rate ({rate}). Must be between 0.0 and 1.0.")
+        }
+    }
+    let mut rng = Rng::new(opts.seed);
+
+    let mut copies = composition
+        .iter()
+        .flat_map(|(hor, n)| std::iter::repeat_n(hor, *n))
+        .filter(|hor| !hor.monomers().is_empty())
+        .collect_vec();
+    if copies.is_empty() {
+        bail!("No StV copies to simulate.")
+    }
+    // Fisher-Yates shuffle.
+    for i in (1..copies.len()).rev() {
+        copies.swap(i, rng.index(i + 1));
+    }
+
+    let mut sequence = vec![];
+    let mut monomers = vec![];
+    let mut records = vec![];
+    for hor in copies {
+        let mut copy_mons = hor.monomers().to_vec();
+        if rng.chance(opts.chimera_rate) {
+            let junctions = (0..copy_mons.len().saturating_sub(1))
+                .filter(|i| !copy_mons[*i].is_chimeric() && !copy_mons[i + 1].is_chimeric())
+                .collect_vec();
+            if !junctions.is_empty() {
+                let i = junctions[rng.index(junctions.len())];
+                let numbers = [copy_mons[i].monomers[0], copy_mons[i + 1].monomers[0]];
+                copy_mons.splice(i..i + 2, [copy_mons[i].with_numbers(&numbers)]);
+            }
+        }
+        let strand = if rng.chance(opts.inversion_rate) {
+            // Monomers are in genome order so an inverted copy is read backwards.
+            copy_mons.reverse();
+            Strand::Minus
+        } else {
+            Strand::Plus
+        };
+
+        let copy_st = sequence.len() as u64;
+        for mon in copy_mons.iter_mut() {
+            mon.strand = Some(strand);
+            let mut mon_seq = library.monomer_sequence(mon)?;
+            if strand == Strand::Minus {
+                mon_seq = reverse_complement(&mon_seq);
+            }
+            let mut n_mutations = 0;
+            for base in mon_seq.iter_mut() {
+                if rng.chance(opts.mutation_rate) {
+                    let new_base = BASES
+                        .iter()
+                        .filter(|b| !b.eq_ignore_ascii_case(base))
+                        .nth(rng.index(3))
+                        .unwrap_or(&b'N');
+                    *base = *new_base;
+                    n_mutations += 1;
+                }
+            }
+            let identity = if mon_seq.is_empty() {
+                100.0
+            } else {
+                (1.0 - n_mutations as f32 / mon_seq.len() as f32) * 100.0
+            };
+            let st = sequence.len() as u64;
+            sequence.extend(mon_seq);
+            monomers.push((st, sequence.len() as u64, identity, mon.clone()));
+        }
+
+        let mut copy_hor = HOR::from_units(&copy_mons[0], vec![]);
+        copy_hor.append(copy_mons)?;
+        records.push(StvRecord::new(
+            chrom,
+            copy_st,
+            sequence.len() as u64,
+            copy_hor,
+        ));
+
+        if rng.chance(opts.insertion_rate) {
+            sequence.extend((0..opts.insertion_length).map(|_| BASES[rng.index(BASES.len())]));
+        }
+    }
+    Ok(SimulatedArray {
+        chrom: chrom.to_owned(),
+        sequence,
+        monomers,
+        records,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::{simulate_array, MonomerLibrary, SimulationOpts, Strand, HOR};
+
+    use super::super::io::monomers_to_stv_records;
+
+    const FASTA: &[u8] = b">S1C1/5/19H1L.1\nACGTTGCAAGCTTACGGATC\n>S1C1/5/19H1L.2\nTTGACCGATAGGCTAACGTA\n>S1C1/5/19H1L.3\nGGCATCAGTTACCGATGACA\n>S1C1/5/19H1L.4\nCATGGTACCAGTTGACGTAC\n";
+
+    fn composition() -> Vec<(HOR, usize)> {
+        vec![
+            (HOR::new("S1C1/5/19H1L.1-4").unwrap(), 20),
+            (HOR::new("S1C1/5/19H1L.1_4").unwrap(), 5),
+        ]
+    }
+
+    fn opts() -> SimulationOpts {
+        SimulationOpts {
+            mutation_rate: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simulate_truth() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let array = simulate_array("sim", &composition(), &library, Some(opts())).unwrap();
+        assert_eq!(array.records.len(), 25);
+        assert_eq!(array.sequence.len(), (20 * 4 + 5 * 2) * 20);
+        assert_eq!(
+            array
+                .records
+                .iter()
+                .filter(|rec| rec.hor.to_string() == "S1C1/5/19H1L.1_4")
+                .count(),
+            5
+        );
+        // Records tile the array.
+        assert!(array
+            .records
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.end == b.st));
+        assert!(array
+            .monomers
+            .iter()
+            .all(|(_, _, identity, _)| *identity == 100.0));
+
+        // Same seed, same array.
+        let other = simulate_array("sim", &composition(), &library, Some(opts())).unwrap();
+        assert_eq!(array, other);
+    }
+
+    #[test]
+    fn test_simulate_recover_stvs() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let composition = [
+            (HOR::new("S1C1/5/19H1L.1-4").unwrap(), 20),
+            (HOR::new("S1C1/5/19H1L.1-3").unwrap(), 5),
+        ];
+        let array = simulate_array("sim", &composition, &library, None).unwrap();
+        let called = monomers_to_stv_records(&array.chrom, &array.monomers).unwrap();
+        let fn_fmt = |recs: &[crate::StvRecord]| {
+            recs.iter()
+                .map(|rec| format!("{}-{}:{}", rec.st, rec.end, rec.hor))
+                .collect_vec()
+        };
+        assert_eq!(fn_fmt(&called), fn_fmt(&array.records));
+    }
+
+    #[test]
+    fn test_simulate_events() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let opts = SimulationOpts {
+            seed: 7,
+            mutation_rate: 0.05,
+            chimera_rate: 0.5,
+            inversion_rate: 0.5,
+            insertion_rate: 0.5,
+            insertion_length: 10,
+        };
+        let array = simulate_array("sim", &composition(), &library, Some(opts)).unwrap();
+        assert!(array
+            .monomers
+            .iter()
+            .any(|(_, _, _, mon)| mon.monomers.len() == 2));
+        assert!(array
+            .records
+            .iter()
+            .any(|rec| rec.strand() == Some(Strand::Minus)));
+        // Insertions leave gaps between records.
+        assert!(array
+            .records
+            .iter()
+            .tuple_windows()
+            .any(|(a, b)| b.st - a.end == 10));
+        assert!(array
+            .monomers
+            .iter()
+            .any(|(_, _, identity, _)| *identity < 100.0));
+    }
+
+    #[test]
+    fn test_simulate_outputs() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let composition = [(HOR::new("S1C1/5/19H1L.1-2").unwrap(), 1)];
+        let array = simulate_array("sim", &composition, &library, Some(opts())).unwrap();
+
+        let mut fasta = vec![];
+        array.write_fasta(&mut fasta).unwrap();
+        assert_eq!(
+            String::from_utf8(fasta).unwrap(),
+            ">sim\nACGTTGCAAGCTTACGGATCTTGACCGATAGGCTAACGTA\n"
+        );
+        let mut bed = vec![];
+        array.write_monomer_bed(&mut bed).unwrap();
+        assert_eq!(
+            String::from_utf8(bed).unwrap(),
+            "sim\t0\t20\tS1C1/5/19H1L.1\t100.00\t+\t0\t20\t0,0,0\nsim\t20\t40\tS1C1/5/19H1L.2\t100.00\t+\t20\t40\t0,0,0\n"
+        );
+        let mut bed = vec![];
+        array.write_stv_bed(&mut bed).unwrap();
+        assert_eq!(
+            String::from_utf8(bed).unwrap(),
+            "sim\t0\t40\tS1C1/5/19H1L.1-2\n"
+        );
+    }
+
+    #[test]
+    fn test_simulate_invalid() {
+        let library = MonomerLibrary::from_reader(FASTA).unwrap();
+        let bad_rate = SimulationOpts {
+            mutation_rate: 1.5,
+            ..Default::default()
+        };
+        assert!(simulate_array("sim", &composition(), &library, Some(bad_rate)).is_err());
+        assert!(simulate_array("sim", &[], &library, None).is_err());
+        let missing = [(HOR::new("S1C1/5/19H1L.1-5").unwrap(), 1)];
+        assert!(simulate_array("sim", &missing, &library, None).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Fixtures shared by the tests of StV modules.
+
+use std::fmt::Display;
+
+use crate::{Monomer, StvRecord, HOR};
+
+use super::io::ContigMonomers;
+
+/// Length of each fixture monomer.
+pub(crate) const MONOMER_LEN: u64 = 171;
+
+/// Tile `S1C1/5/19H1L` monomers every [`MONOMER_LEN`] bp at 100% identity.
+/// * `nums` are monomer numbers. ex. `3` or `3/5`
+pub(crate) fn monomers(nums: &[impl Display]) -> Vec<(u64, u64, f32, Monomer)> {
+    nums.iter()
+        .enumerate()
+        .map(|(i, num)| {
+            let st = i as u64 * MONOMER_LEN;
+            let mon = Monomer::new(&format!("S1C1/5/19H1L.{num}")).unwrap();
+            (st, st + MONOMER_LEN, 100.0, mon)
+        })
+        .collect()
+}
+
+/// Tile [`monomers`] on each contig.
+pub(crate) fn contig_monomers(contigs: &[(&str, &[&str])]) -> ContigMonomers {
+    contigs
+        .iter()
+        .map(|(chrom, nums)| (chrom.to_string(), monomers(nums)))
+        .collect()
+}
+
+/// Construct StVs from `(chrom, st, end, hor)`.
+pub(crate) fn records(stvs: &[(&str, u64, u64, &str)]) -> Vec<StvRecord> {
+    stvs.iter()
+        .map(|(chrom, st, end, hor)| StvRecord::new(*chrom, *st, *end, HOR::new(hor).unwrap()))
+        .collect()
+}
+
+/// Tile StVs on `chrom` every `len` bp.
+pub(crate) fn tiled_records(chrom: &str, hors: &[&str], len: u64) -> Vec<StvRecord> {
+    hors.iter()
+        .enumerate()
+        .map(|(i, hor)| {
+            let st = i as u64 * len;
+            StvRecord::new(chrom, st, st + len, HOR::new(hor).unwrap())
+        })
+        .collect()
+}
@@ -0,0 +1,350 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{Monomer, Strand};
+
+/// Number of columns in a `BED9` monomer file.
+const N_COLUMNS: usize = 9;
+
+/// Options for [`validate_monomer_bed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationOpts {
+    /// Maximum length in bp of a monomer.
+    pub max_monomer_len: u64,
+    /// Maximum overlap in bp between consecutive monomers.
+    pub max_overlap: u64,
+}
+
+impl Default for ValidationOpts {
+    fn default() -> Self {
+        Self {
+            max_monomer_len: 300,
+            max_overlap: 0,
+        }
+    }
+}
+
+/// An issue found by [`validate_monomer_bed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BedIssueKind {
+    /// Line has fewer than 9 tab-delimited columns.
+    WrongColumnCount(usize),
+    /// A numeric column can't be parsed. `(column, value)` with a `1`-based column.
+    InvalidNumber(usize, String),
+    /// Strand is not `+` or `-`.
+    InvalidStrand(String),
+    /// Color is not `r,g,b` or `0`. Read as is.
+    InvalidRgb(String),
+    /// Name is not a valid [`Monomer`].
+    UnparsableName(String),
+    /// Monomer has an end at or before its start.
+    ZeroLength,
+    /// Monomer is longer than [`ValidationOpts::max_monomer_len`].
+    OversizedLength(u64),
+    /// Monomer starts before the previous monomer on the contig. Has the previous start.
+    Unsorted(u64),
+    /// Monomer overlaps the previous monomer on the contig by more than [`ValidationOpts::max_overlap`]. Has the overlap in bp.
+    Overlap(u64),
+    /// Monomer is from a different HOR than the first monomer on the contig. `(first, current)` HOR names.
+    MixedHOR(String, String),
+}
+
+impl BedIssueKind {
+    /// Check if the issue prevents the line from being read by [`crate::read_monomers_from_bed`].
+    /// * Other issues are read but may give incorrect StVs.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            BedIssueKind::WrongColumnCount(_)
+                | BedIssueKind::InvalidNumber(_, _)
+                | BedIssueKind::InvalidStrand(_)
+                | BedIssueKind::UnparsableName(_)
+        )
+    }
+}
+
+/// An issue in a monomer `BED` file with its location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BedIssue {
+    /// `1`-based line number.
+    pub line: usize,
+    /// Contig in the first column of the line.
+    pub chrom: String,
+    pub kind: BedIssueKind,
+}
+
+impl Display for BedIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BedIssueKind::WrongColumnCount(n) => {
+                write!(f, "Expected at least {N_COLUMNS} columns, found {n}.")
+            }
+            BedIssueKind::InvalidNumber(col, value) => {
+                write!(f, "Invalid number in column {col}, {value}.")
+            }
+            BedIssueKind::InvalidStrand(strand) => write!(f, "Invalid strand, {strand}."),
+            BedIssueKind::InvalidRgb(rgb) => write!(f, "Invalid RGB, {rgb}."),
+            BedIssueKind::UnparsableName(name) => write!(f, "Cannot parse monomer, {name}."),
+            BedIssueKind::ZeroLength => write!(f, "Monomer has no length."),
+            BedIssueKind::OversizedLength(len) => write!(f, "Monomer is too long, {len} bp."),
+            BedIssueKind::Unsorted(prev_st) => {
+                write!(f, "Monomer starts before previous monomer at {prev_st}.")
+            }
+            BedIssueKind::Overlap(overlap) => {
+                write!(f, "Monomer overlaps previous monomer by {overlap} bp.")
+            }
+            BedIssueKind::MixedHOR(first, curr) => {
+                write!(f, "Monomer from {curr} in contig of {first}.")
+            }
+        }
+    }
+}
+
+impl Display for BedIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {} ({}): {}", self.line, self.chrom, self.kind)
+    }
+}
+
+/// Parse a `BED9` RGB column, `r,g,b` or `0`.
+fn parse_rgb(rgb: &str) -> Option<[u8; 3]> {
+    match rgb.split(',').collect::<Vec<&str>>()[..] {
+        ["0"] => Some([0, 0, 0]),
+        [r, g, b] => Some([r.parse().ok()?, g.parse().ok()?, b.parse().ok()?]),
+        _ => None,
+    }
+}
+
+/// Per-contig state while validating.
+#[derive(Default)]
+struct ContigState {
+    /// Previous monomer as `(st, end)`.
+    prev: Option<(u64, u64)>,
+    hor_name: Option<String>,
+}
+
+/// Check a `BED9` file of [`crate::MonomerRecord`]s for issues before conversion.
+/// * All issues are reported rather than stopping at the first.
+/// * Empty lines and lines starting with `#` are skipped.
+///
+/// # Args
+/// * `bedfile`
+///     * Path to `BED9` file.
+/// * `opts`
+///     * [`ValidationOpts`]. Uses default if omitted.
+///
+/// # Returns
+/// * [`BedIssue`]s in line order or an error if the file cannot be read.
+///
+/// # Examples
+/// ```
+/// use rs_asat_hor::validate_monomer_bed;
+///
+/// let issues = validate_monomer_bed("test/mons.bed", None).unwrap();
+/// assert!(issues.is_empty());
+/// ```
+pub fn validate_monomer_bed(
+    bedfile: impl AsRef<Path>,
+    opts: Option<ValidationOpts>,
+) -> eyre::Result<Vec<BedIssue>> {
+    let file = std::fs::File::open(bedfile)?;
+    validate_monomer_bed_reader(BufReader::new(file), opts)
+}
+
+/// Check `BED9` [`crate::MonomerRecord`]s from a reader for issues. See [`validate_monomer_bed`].
+pub fn validate_monomer_bed_reader(
+    reader: impl BufRead,
+    opts: Option<ValidationOpts>,
+) -> eyre::Result<Vec<BedIssue>> {
+    let opts = opts.unwrap_or_default();
+    let mut issues = vec![];
+    let mut contigs: HashMap<String, ContigState> = HashMap::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        // Same as when reading records.
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_num = i + 1;
+        let columns: Vec<&str> = line.split('\t').collect();
+        let chrom = columns[0].to_owned();
+        let mut fn_issue = |kind: BedIssueKind| {
+            issues.push(BedIssue {
+                line: line_num,
+                chrom: chrom.clone(),
+                kind,
+            })
+        };
+        if columns.len() < N_COLUMNS {
+            fn_issue(BedIssueKind::WrongColumnCount(columns.len()));
+            continue;
+        }
+
+        let mut fn_parse_u64 = |col: usize| match columns[col].parse::<u64>() {
+            Ok(num) => Some(num),
+            Err(_) => {
+                fn_issue(BedIssueKind::InvalidNumber(
+                    col + 1,
+                    columns[col].to_owned(),
+                ));
+                None
+            }
+        };
+        let (st, end) = (fn_parse_u64(1), fn_parse_u64(2));
+        fn_parse_u64(6);
+        fn_parse_u64(7);
+        if columns[4].parse::<f32>().is_err() {
+            fn_issue(BedIssueKind::InvalidNumber(5, columns[4].to_owned()));
+        }
+        if Strand::from_str(columns[5]).is_err() {
+            fn_issue(BedIssueKind::InvalidStrand(columns[5].to_owned()));
+        }
+        if parse_rgb(columns[8]).is_none() {
+            fn_issue(BedIssueKind::InvalidRgb(columns[8].to_owned()));
+        }
+        let monomer = Monomer::new(columns[3]);
+        if monomer.is_err() {
+            fn_issue(BedIssueKind::UnparsableName(columns[3].to_owned()));
+        }
+
+        let state = contigs.entry(chrom.clone()).or_default();
+        if let (Some(st), Some(end)) = (st, end) {
+            if end <= st {
+                fn_issue(BedIssueKind::ZeroLength);
+            } else if end - st > opts.max_monomer_len {
+                fn_issue(BedIssueKind::OversizedLength(end - st));
+            }
+            if let Some((prev_st, prev_end)) = state.prev {
+                if st < prev_st {
+                    fn_issue(BedIssueKind::Unsorted(prev_st));
+                } else if prev_end.saturating_sub(st) > opts.max_overlap {
+                    fn_issue(BedIssueKind::Overlap(prev_end - st));
+                }
+            }
+            state.prev = Some((st, end));
+        }
+        if let Ok(monomer) = monomer {
+            let hor_name = monomer.hor_name();
+            match &state.hor_name {
+                Some(first) if *first != hor_name => {
+                    fn_issue(BedIssueKind::MixedHOR(first.clone(), hor_name))
+                }
+                Some(_) => (),
+                None => state.hor_name = Some(hor_name),
+            }
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{validate_monomer_bed_reader, BedIssueKind, ValidationOpts};
+
+    fn kinds(bed: &str, opts: Option<ValidationOpts>) -> Vec<(usize, BedIssueKind)> {
+        validate_monomer_bed_reader(bed.as_bytes(), opts)
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.line, issue.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_parse_errors() {
+        let bed = "\
+# header
+chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t0,0,0
+chr1\t171\t342\tS2C15H1L.2\t99.0\t+\t171
+chr1\tx\t513\tS2C15H1L.3\tNA\t+\t342\t513\t0,0,0
+chr1\t513\t684\tnot_a_monomer\t99.0\t.\t513\t684\t0,0,0
+";
+        let issues = kinds(bed, None);
+        assert_eq!(
+            issues,
+            [
+                (3, BedIssueKind::WrongColumnCount(7)),
+                (4, BedIssueKind::InvalidNumber(2, "x".to_owned())),
+                (4, BedIssueKind::InvalidNumber(5, "NA".to_owned())),
+                (5, BedIssueKind::InvalidStrand(".".to_owned())),
+                (5, BedIssueKind::UnparsableName("not_a_monomer".to_owned())),
+            ]
+        );
+        assert!(issues.iter().all(|(_, kind)| kind.is_error()));
+    }
+
+    #[test]
+    fn test_validate_coordinates() {
+        let bed = "\
+chr1\t171\t342\tS2C15H1L.2\t99.0\t+\t171\t342\t0,0,0
+chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t0,0,0
+chr1\t100\t100\tS2C15H1L.2\t99.0\t+\t100\t100\t0,0,0
+chr1\t150\t700\tS2C15H1L.3\t99.0\t+\t150\t700\t0,0,0
+chr2\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t0,0,0
+";
+        assert_eq!(
+            kinds(bed, None),
+            [
+                (2, BedIssueKind::Unsorted(171)),
+                (3, BedIssueKind::ZeroLength),
+                (3, BedIssueKind::Overlap(71)),
+                (4, BedIssueKind::OversizedLength(550)),
+            ]
+        );
+        let bed = "\
+chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t0,0,0
+chr1\t170\t341\tS2C15H1L.2\t99.0\t+\t170\t341\t0,0,0
+chr1\t300\t471\tS2C15H3d.3\t99.0\t+\t300\t471\t0,0,0
+";
+        let issues = kinds(bed, None);
+        assert_eq!(
+            issues,
+            [
+                (2, BedIssueKind::Overlap(1)),
+                (3, BedIssueKind::Overlap(41)),
+                (
+                    3,
+                    BedIssueKind::MixedHOR("S2C15H1L".to_owned(), "S2C15H3d".to_owned())
+                ),
+            ]
+        );
+        assert!(!issues[0].1.is_error());
+        let opts = ValidationOpts {
+            max_overlap: 50,
+            ..Default::default()
+        };
+        assert_eq!(kinds(bed, Some(opts)).len(), 1);
+    }
+
+    #[test]
+    fn test_validate_rgb() {
+        let bed = "chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t.\n";
+        let issues = kinds(bed, None);
+        assert_eq!(issues, [(1, BedIssueKind::InvalidRgb(".".to_owned()))]);
+        // Read as is so not an error.
+        assert!(!issues[0].1.is_error());
+    }
+
+    #[test]
+    fn test_validate_whitespace() {
+        // Read without issues by crate::read_monomer_records.
+        let bed = "  # header\r\n chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\t0,0,0 \r\n\t\n";
+        assert!(kinds(bed, None).is_empty());
+    }
+
+    #[test]
+    fn test_validate_display() {
+        let bed = "chr1\t0\t171\tS2C15H1L.1\t99.0\t+\t0\t171\n";
+        let issues = validate_monomer_bed_reader(bed.as_bytes(), None).unwrap();
+        assert_eq!(
+            issues[0].to_string(),
+            "Line 1 (chr1): Expected at least 9 columns, found 8."
+        );
+    }
+}
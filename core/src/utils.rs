@@ -0,0 +1,39 @@
+/// Levenshtein distance between two sequences using two rows.
+/// * Elements are compared with `fn_eq`.
+pub(crate) fn edit_distance<T, F>(seq_1: &[T], seq_2: &[T], fn_eq: F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let mut prev_row = (0..=seq_2.len()).collect::<Vec<usize>>();
+    let mut curr_row = vec![0; seq_2.len() + 1];
+    for (i, elem_1) in seq_1.iter().enumerate() {
+        curr_row[0] = i + 1;
+        for (j, elem_2) in seq_2.iter().enumerate() {
+            let cost = usize::from(!fn_eq(elem_1, elem_2));
+            curr_row[j + 1] = (prev_row[j] + cost)
+                .min(prev_row[j + 1] + 1)
+                .min(curr_row[j] + 1);
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+    prev_row[seq_2.len()]
+}
+
+/// Reverse complement a nucleotide sequence.
+/// * Case is preserved. Non-nucleotide characters are kept as is.
+pub(crate) fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|nt| match nt {
+            b'A' => b'T',
+            b'T' => b'A',
+            b'G' => b'C',
+            b'C' => b'G',
+            b'a' => b't',
+            b't' => b'a',
+            b'g' => b'c',
+            b'c' => b'g',
+            _ => *nt,
+        })
+        .collect()
+}
@@ -21,7 +21,7 @@ impl PyHORIterator {
 
 #[pyclass(name = "HOR")]
 /// A Python wrapper class for [`HOR`]
-pub(crate) struct PyHOR(HOR);
+pub(crate) struct PyHOR(pub(crate) HOR);
 
 #[pymethods]
 impl PyHOR {
@@ -2,13 +2,16 @@ pub use pyo3::prelude::*;
 
 mod hor;
 mod monomer;
+mod query;
 
 use hor::PyHOR;
 use monomer::PyMonomer;
+use query::PyStvQuery;
 
 #[pymodule]
 fn asat_hor(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyMonomer>()?;
     m.add_class::<PyHOR>()?;
+    m.add_class::<PyStvQuery>()?;
     Ok(())
 }
@@ -0,0 +1,23 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use rs_asat_hor::StvQuery;
+
+use crate::hor::PyHOR;
+
+#[pyclass(name = "StvQuery")]
+/// A Python wrapper class for [`StvQuery`]
+pub(crate) struct PyStvQuery(StvQuery);
+
+#[pymethods]
+impl PyStvQuery {
+    #[new]
+    fn new(value: &str) -> PyResult<Self> {
+        StvQuery::new(value)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+            .map(PyStvQuery)
+    }
+
+    fn is_match(&self, hor: PyRef<'_, PyHOR>) -> bool {
+        self.0.is_match(&hor.0)
+    }
+}